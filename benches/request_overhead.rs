@@ -0,0 +1,33 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use loadtest::request::body::Body;
+use loadtest::request::definition::{RequestDefinition, ResolvedRequest};
+use loadtest::request::reqwest_based::ReqwestConnection;
+use loadtest::tsp_specific::cities;
+
+fn request_overhead(c: &mut Criterion) {
+    let connection = ReqwestConnection::new("http://localhost");
+    let payload = cities::twenty_nine();
+    let definition = RequestDefinition::post("tsp", Body::json(&payload).unwrap());
+
+    let mut group = c.benchmark_group("29 cities");
+    group.bench_function("pre-serialized body", |b| {
+        b.iter(|| {
+            connection
+                .prepare(black_box(&ResolvedRequest::from(&definition)))
+                .unwrap()
+        })
+    });
+    group.bench_function("serialized per request", |b| {
+        b.iter(|| {
+            let definition =
+                RequestDefinition::post("tsp", Body::json(black_box(&payload)).unwrap());
+            connection
+                .prepare(&ResolvedRequest::from(&definition))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, request_overhead);
+criterion_main!(benches);
@@ -0,0 +1,158 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+pub const EXIT_THRESHOLDS_FAILED: u8 = 1;
+pub const EXIT_INVALID_INPUT: u8 = 2;
+pub const EXIT_OUTPUT_FAILED: u8 = 3;
+
+/// HTTP load testing with virtual users, arrival rates and load profiles.
+#[derive(Debug, Parser)]
+#[command(name = "loadtest", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a scenario and report the results.
+    Run(RunArgs),
+    /// Check that a scenario file parses without running it.
+    Validate { scenario: PathBuf },
+    /// Render results previously written with `run --format json`.
+    Report(ReportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    pub scenario: PathBuf,
+    /// Overrides the scenario host.
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Number of virtual users.
+    #[arg(long, short = 'c')]
+    pub concurrency: Option<usize>,
+    /// Run for this many seconds instead of the scenario run mode.
+    #[arg(long, short = 'd', conflicts_with = "iterations")]
+    pub duration: Option<f64>,
+    /// Run this many iterations per user instead of the scenario run mode.
+    #[arg(long, short = 'n')]
+    pub iterations: Option<usize>,
+    /// Send requests at a constant rate per second.
+    #[arg(long, short = 'r')]
+    pub rate: Option<f64>,
+    /// Give up on requests after this many seconds.
+    #[arg(long, short = 't')]
+    pub timeout: Option<f64>,
+    /// Seed for random request mixes and think times, to repeat a run.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Run virtual users as tokio tasks instead of threads.
+    #[arg(long = "async")]
+    pub run_async: bool,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    pub results: PathBuf,
+    /// Evaluate the thresholds of this scenario against the results.
+    #[arg(long)]
+    pub scenario: Option<PathBuf>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    #[arg(long, short = 'f', value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Write the report to this file instead of stdout.
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn run_with_overrides() {
+        let cli = Cli::try_parse_from([
+            "loadtest",
+            "run",
+            "tsp.yaml",
+            "--host",
+            "http://staging",
+            "-c",
+            "8",
+            "--duration",
+            "30",
+            "--rate",
+            "100",
+            "--timeout",
+            "2.5",
+            "--seed",
+            "42",
+            "--async",
+            "--format",
+            "json",
+            "-o",
+            "results.json",
+        ])
+        .unwrap();
+
+        let Command::Run(run) = cli.command else {
+            panic!("expected run command");
+        };
+        assert_eq!(run.scenario, PathBuf::from("tsp.yaml"));
+        assert_eq!(run.host.as_deref(), Some("http://staging"));
+        assert_eq!(run.concurrency, Some(8));
+        assert_eq!(run.duration, Some(30.0));
+        assert_eq!(run.iterations, None);
+        assert_eq!(run.rate, Some(100.0));
+        assert_eq!(run.timeout, Some(2.5));
+        assert_eq!(run.seed, Some(42));
+        assert!(run.run_async);
+        assert_eq!(run.output.format, OutputFormat::Json);
+        assert_eq!(run.output.output, Some(PathBuf::from("results.json")));
+    }
+
+    #[test]
+    fn duration_conflicts_with_iterations() {
+        assert!(Cli::try_parse_from([
+            "loadtest",
+            "run",
+            "tsp.yaml",
+            "--duration",
+            "30",
+            "--iterations",
+            "3"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn report_defaults_to_text() {
+        let cli = Cli::try_parse_from(["loadtest", "report", "results.json"]).unwrap();
+
+        let Command::Report(report) = cli.command else {
+            panic!("expected report command");
+        };
+        assert_eq!(report.output.format, OutputFormat::Text);
+        assert_eq!(report.scenario, None);
+    }
+}
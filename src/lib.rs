@@ -1,5 +1,7 @@
 pub mod load_test;
 pub mod request;
+pub mod scenario;
+pub mod statistics;
 pub mod tsp_specific;
 
 pub use load_test::core::LoadTest;
@@ -1,130 +1,619 @@
-use crate::request::definition::RequestDefinition;
-use crate::request::interface::{HTTPClient, TimedResponse};
+use crate::load_test::executor::{ArrivalRate, Executor};
+use crate::load_test::feeder::Feeder;
+use crate::load_test::request_mix::{RequestMix, RequestPicker};
+use crate::load_test::result::{Failure, Sample, UserResult};
+use crate::load_test::run_mode::RunMode;
+use crate::load_test::think_time::ThinkTime;
+use crate::request::definition::{RequestDefinition, ResolvedRequest};
+use crate::request::interface::{AsyncHTTPClient, HTTPClient, RequestError, TimedResponse};
+use crate::request::variables::Variables;
+use crate::statistics::histogram::LatencyRecorder;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub struct LoadTest<'a, R>
+const INACTIVE_USER_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+pub struct LoadTest<R> {
+    connection: Arc<R>,
+    to_call: Vec<RequestDefinition>,
+    virtual_users: usize,
+    run_mode: RunMode,
+    executor: Executor,
+    latency_recorder: LatencyRecorder,
+    retain_samples: bool,
+    think_time: Option<ThinkTime>,
+    iteration_think_time: Option<ThinkTime>,
+    pacing: Option<Duration>,
+    request_mix: RequestMix,
+    seed: Option<u64>,
+    feeders: Vec<Arc<Feeder>>,
+}
+
+impl<R> Clone for LoadTest<R> {
+    fn clone(&self) -> Self {
+        Self {
+            connection: Arc::clone(&self.connection),
+            to_call: self.to_call.clone(),
+            virtual_users: self.virtual_users,
+            run_mode: self.run_mode.clone(),
+            executor: self.executor,
+            latency_recorder: self.latency_recorder.clone(),
+            retain_samples: self.retain_samples,
+            think_time: self.think_time,
+            iteration_think_time: self.iteration_think_time,
+            pacing: self.pacing,
+            request_mix: self.request_mix,
+            seed: self.seed,
+            feeders: self.feeders.clone(),
+        }
+    }
+}
+
+impl<R> LoadTest<R> {
+    pub fn new(connection: Arc<R>, to_call: Vec<RequestDefinition>) -> Self {
+        Self {
+            connection,
+            to_call,
+            virtual_users: 1,
+            run_mode: RunMode::default(),
+            executor: Executor::default(),
+            latency_recorder: LatencyRecorder::default(),
+            retain_samples: false,
+            think_time: None,
+            iteration_think_time: None,
+            pacing: None,
+            request_mix: RequestMix::default(),
+            seed: None,
+            feeders: vec![],
+        }
+    }
+
+    pub fn with_virtual_users(mut self, virtual_users: usize) -> Self {
+        self.virtual_users = virtual_users;
+        self
+    }
+
+    pub fn with_run_mode(mut self, run_mode: RunMode) -> Self {
+        self.run_mode = run_mode;
+        self
+    }
+
+    pub fn with_executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn with_latency_recorder(mut self, latency_recorder: LatencyRecorder) -> Self {
+        self.latency_recorder = latency_recorder;
+        self
+    }
+
+    pub fn with_retained_samples(mut self, retain_samples: bool) -> Self {
+        self.retain_samples = retain_samples;
+        self
+    }
+
+    /// Pause after every request that has no think time of its own. Think times and
+    /// pacing only shape closed-loop users; arrival rates already fix when requests go out.
+    pub fn with_think_time(mut self, think_time: ThinkTime) -> Self {
+        self.think_time = Some(think_time);
+        self
+    }
+
+    pub fn with_iteration_think_time(mut self, think_time: ThinkTime) -> Self {
+        self.iteration_think_time = Some(think_time);
+        self
+    }
+
+    /// Start iterations of a user at least `pacing` apart.
+    pub fn with_pacing(mut self, pacing: Duration) -> Self {
+        self.pacing = Some(pacing);
+        self
+    }
+
+    pub fn with_request_mix(mut self, request_mix: RequestMix) -> Self {
+        self.request_mix = request_mix;
+        self
+    }
+
+    /// Seeds the random choices of every virtual user, so they repeat from run to run.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Every feeder hands a row to each iteration of a closed-loop user or an arrival-rate
+    /// worker. Users stop as soon as one feeder does.
+    pub fn with_feeder(mut self, feeder: Feeder) -> Self {
+        self.feeders.push(Arc::new(feeder));
+        self
+    }
+
+    pub fn request_definitions(&self) -> &[RequestDefinition] {
+        &self.to_call
+    }
+
+    fn spawned_users(&self) -> usize {
+        match (&self.executor, &self.run_mode) {
+            (Executor::ClosedLoop, RunMode::Profile(profile)) => profile.max_users(),
+            _ => self.virtual_users,
+        }
+    }
+
+    fn empty_result(&self, user: usize) -> UserResult {
+        UserResult::new(
+            user,
+            vec![self.latency_recorder.clone(); self.to_call.len()],
+            self.retain_samples,
+        )
+    }
+
+    fn rng(&self, user: usize) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(user as u64)),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    fn think_time_after(&self, definition: usize, rng: &mut StdRng) -> Option<Duration> {
+        self.to_call[definition]
+            .think_time()
+            .or(self.think_time)
+            .map(|think_time| think_time.sample(rng))
+    }
+
+    fn next_iteration_start(
+        &self,
+        iteration_start: Duration,
+        elapsed: Duration,
+        rng: &mut StdRng,
+    ) -> Option<Duration> {
+        let after_think_time = self
+            .iteration_think_time
+            .map(|think_time| elapsed + think_time.sample(rng));
+        let paced = self.pacing.map(|pacing| iteration_start + pacing);
+        after_think_time.max(paced)
+    }
+
+    /// Sets the columns of every feeder's next row, `false` once a feeder stops.
+    fn feed(&self, user: usize, rng: &mut StdRng, variables: &mut Variables) -> bool {
+        for feeder in &self.feeders {
+            let Some(row) = feeder.next_row(user, rng) else {
+                return false;
+            };
+            for (name, value) in row {
+                variables.set(name, value.clone());
+            }
+        }
+        true
+    }
+
+    /// How long to sleep to reach `until`, cut short where the run mode ends.
+    fn pause(&self, started: Instant, until: Duration) -> Duration {
+        self.run_mode
+            .deadline()
+            .map_or(until, |deadline| until.min(deadline))
+            .saturating_sub(started.elapsed())
+    }
+}
+
+impl<R> LoadTest<R>
 where
-    R: HTTPClient,
+    R: HTTPClient + Send + Sync,
 {
-    connection: &'a R,
-    to_call: Vec<RequestDefinition<'a>>,
+    pub fn run(&self) -> Vec<UserResult> {
+        let started = Instant::now();
+        let next_iteration = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            let users: Vec<_> = (0..self.spawned_users())
+                .map(|user| {
+                    let next_iteration = &next_iteration;
+                    scope.spawn(move || {
+                        let own_connection = self.connection.for_user(user);
+                        let connection = own_connection.as_ref().unwrap_or(&self.connection);
+                        Self::run_user(
+                            connection,
+                            VirtualUser::new(self, user, started, next_iteration),
+                        )
+                    })
+                })
+                .collect();
+
+            users
+                .into_iter()
+                .map(|user| user.join().expect("Virtual user panicked."))
+                .collect()
+        })
+    }
+
+    fn run_user(connection: &R, mut virtual_user: VirtualUser<'_, R>) -> UserResult {
+        loop {
+            match virtual_user.next_action() {
+                Action::Sleep(pause) => thread::sleep(pause),
+                Action::Send(step) => {
+                    let request = virtual_user.resolve(&step);
+                    let request_send = Instant::now();
+                    let response = connection.send(&request);
+                    virtual_user.complete(step, &request, response, request_send.elapsed());
+                }
+                Action::Stop => return virtual_user.result,
+            }
+        }
+    }
 }
 
-impl<'a, R> LoadTest<'a, R>
+impl<R> LoadTest<R>
 where
-    R: HTTPClient,
+    R: AsyncHTTPClient + Send + Sync + 'static,
 {
-    pub fn new(connection: &'a R, to_call: Vec<RequestDefinition<'a>>) -> Self {
+    /// Runs every virtual user as a tokio task instead of an OS thread, so it has to be
+    /// awaited inside a tokio runtime.
+    pub async fn run_async(&self) -> Vec<UserResult> {
+        let load_test = Arc::new(self.clone());
+        let started = Instant::now();
+        let next_iteration = Arc::new(AtomicUsize::new(0));
+
+        let users: Vec<_> = (0..self.spawned_users())
+            .map(|user| {
+                let load_test = Arc::clone(&load_test);
+                let next_iteration = Arc::clone(&next_iteration);
+                tokio::spawn(async move {
+                    let own_connection = load_test.connection.for_user(user);
+                    let connection = own_connection.as_ref().unwrap_or(&load_test.connection);
+                    let virtual_user = VirtualUser::new(&load_test, user, started, &next_iteration);
+                    Self::run_user_async(connection, virtual_user).await
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(users.len());
+        for user in users {
+            results.push(user.await.expect("Virtual user panicked."));
+        }
+        results
+    }
+
+    async fn run_user_async(connection: &R, mut virtual_user: VirtualUser<'_, R>) -> UserResult {
+        loop {
+            match virtual_user.next_action() {
+                Action::Sleep(pause) => tokio::time::sleep(pause).await,
+                Action::Send(step) => {
+                    let request = virtual_user.resolve(&step);
+                    let request_send = Instant::now();
+                    let response = connection.send(&request).await;
+                    virtual_user.complete(step, &request, response, request_send.elapsed());
+                }
+                Action::Stop => return virtual_user.result,
+            }
+        }
+    }
+}
+
+/// What a virtual user does next. The threaded and the async runner both carry these
+/// out, so everything but sleeping and sending is shared between them.
+enum Action {
+    Sleep(Duration),
+    Send(Step),
+    Stop,
+}
+
+struct Step {
+    definition: usize,
+    intended_send: Duration,
+    actual_send: Duration,
+}
+
+/// The state of one virtual user between its requests.
+struct VirtualUser<'a, R> {
+    load_test: &'a LoadTest<R>,
+    user: usize,
+    started: Instant,
+    next_iteration: &'a AtomicUsize,
+    picker: Option<RequestPicker>,
+    rng: StdRng,
+    variables: Variables,
+    result: UserResult,
+    iteration: usize,
+    step: usize,
+    iteration_start: Duration,
+    resume_at: Option<Duration>,
+    scheduled: Option<(usize, Duration)>,
+}
+
+impl<'a, R> VirtualUser<'a, R> {
+    fn new(
+        load_test: &'a LoadTest<R>,
+        user: usize,
+        started: Instant,
+        next_iteration: &'a AtomicUsize,
+    ) -> Self {
         Self {
-            connection,
-            to_call,
+            load_test,
+            user,
+            started,
+            next_iteration,
+            picker: RequestPicker::new(load_test.request_mix, &load_test.to_call),
+            rng: load_test.rng(user),
+            variables: Variables::default(),
+            result: load_test.empty_result(user),
+            iteration: 0,
+            step: 0,
+            iteration_start: Duration::ZERO,
+            resume_at: None,
+            scheduled: None,
         }
     }
-    pub fn run(&self) -> Vec<TimedResponse> {
-        self.to_call
-            .iter()
-            .map(|post_request_data| match post_request_data {
-                RequestDefinition::POST { endpoint, to_json } => {
-                    self.connection.post(endpoint, to_json)
+
+    fn next_action(&mut self) -> Action {
+        let Some(picker) = &self.picker else {
+            return Action::Stop;
+        };
+        if let Some(resume_at) = self.resume_at.take() {
+            return Action::Sleep(self.load_test.pause(self.started, resume_at));
+        }
+        let requests_per_iteration = picker.requests_per_iteration();
+        match self.load_test.executor {
+            Executor::ClosedLoop => self.next_closed_loop(requests_per_iteration),
+            Executor::ConstantArrivalRate(rate) => self.next_arrival(requests_per_iteration, rate),
+        }
+    }
+
+    fn next_closed_loop(&mut self, requests_per_iteration: usize) -> Action {
+        let load_test = self.load_test;
+        let elapsed = self.started.elapsed();
+        if self.step == requests_per_iteration {
+            self.step = 0;
+            self.iteration += 1;
+            if let Some(next_start) =
+                load_test.next_iteration_start(self.iteration_start, elapsed, &mut self.rng)
+            {
+                self.resume_at = Some(next_start);
+                return self.next_action();
+            }
+        }
+        if self.step == 0 {
+            if !load_test.run_mode.allows_iteration(self.iteration, elapsed) {
+                return Action::Stop;
+            }
+            if !load_test.run_mode.user_active(self.user, elapsed) {
+                return Action::Sleep(INACTIVE_USER_POLL_INTERVAL);
+            }
+            if !load_test.feed(self.user, &mut self.rng, &mut self.variables) {
+                return Action::Stop;
+            }
+            self.iteration_start = elapsed;
+        }
+        let definition = self.pick();
+        if !load_test.run_mode.allows_request(elapsed) {
+            return Action::Stop;
+        }
+        self.send(definition, elapsed, elapsed)
+    }
+
+    /// Workers claim whole iterations, so the steps of one iteration share a worker's
+    /// variables and feeder rows. Every request keeps the send time of its position in
+    /// the run.
+    fn next_arrival(&mut self, requests_per_iteration: usize, rate: ArrivalRate) -> Action {
+        let load_test = self.load_test;
+        let (definition, intended) = match self.scheduled.take() {
+            Some(scheduled) => scheduled,
+            None => {
+                if self.step == requests_per_iteration {
+                    self.step = 0;
                 }
-                RequestDefinition::GET { endpoint } => self.connection.get(endpoint),
-            })
-            .filter_map(|response_result| response_result.ok())
-            .collect()
+                if self.step == 0 {
+                    self.iteration = self.next_iteration.fetch_add(1, Ordering::Relaxed);
+                    let first_request = self.iteration * requests_per_iteration;
+                    let iteration_start = rate.intended_send(first_request);
+                    if !load_test
+                        .run_mode
+                        .allows_iteration(self.iteration, iteration_start)
+                        || !load_test.feed(self.user, &mut self.rng, &mut self.variables)
+                    {
+                        return Action::Stop;
+                    }
+                }
+                let intended =
+                    rate.intended_send(self.iteration * requests_per_iteration + self.step);
+                if !load_test.run_mode.allows_request(intended) {
+                    return Action::Stop;
+                }
+                (self.pick(), intended)
+            }
+        };
+        let elapsed = self.started.elapsed();
+        if elapsed < intended {
+            self.scheduled = Some((definition, intended));
+            return Action::Sleep(intended - elapsed);
+        }
+        self.send(definition, intended, elapsed)
+    }
+
+    /// The definition of the current step, which then counts as done.
+    fn pick(&mut self) -> usize {
+        let picker = self.picker.as_ref().expect("users without requests stop");
+        let definition = picker.pick(self.step, &mut self.rng);
+        self.step += 1;
+        definition
+    }
+
+    fn send(&self, definition: usize, intended_send: Duration, actual_send: Duration) -> Action {
+        Action::Send(Step {
+            definition,
+            intended_send,
+            actual_send,
+        })
+    }
+
+    /// The request of `step` with this user's variables substituted.
+    fn resolve(&self, step: &Step) -> ResolvedRequest<'a> {
+        self.load_test.to_call[step.definition].resolve(&self.variables)
+    }
+
+    /// Extracts variables from and records the response to the request of `step`.
+    fn complete(
+        &mut self,
+        step: Step,
+        request: &ResolvedRequest<'_>,
+        response: Result<TimedResponse, RequestError>,
+        elapsed: Duration,
+    ) {
+        let load_test = self.load_test;
+        let Step {
+            definition,
+            intended_send,
+            actual_send,
+        } = step;
+        if let Ok(response) = &response {
+            request.definition().extract(response, &mut self.variables);
+        }
+        if load_test.executor == Executor::ClosedLoop {
+            if let Some(think_time) = load_test.think_time_after(definition, &mut self.rng) {
+                self.resume_at = Some(self.started.elapsed() + think_time);
+            }
+        }
+
+        let result = &mut self.result;
+        let stage = load_test.run_mode.stage_at(intended_send);
+        for (check, assertion) in request.definition().checks().iter().enumerate() {
+            let passed = response
+                .as_ref()
+                .is_ok_and(|response| assertion.evaluate(response));
+            result.record_check(definition, check, passed);
+        }
+        match response {
+            Ok(response) => {
+                result.record_status(definition, response.status_class());
+                if response.status_class().is_error() {
+                    result.record_failure(
+                        Failure::new(
+                            definition,
+                            intended_send,
+                            actual_send,
+                            response.response_time(),
+                            RequestError::Status(response.status().as_u16()),
+                        )
+                        .with_stage(stage),
+                    );
+                } else {
+                    result.record(
+                        Sample::new(definition, intended_send, actual_send, response)
+                            .with_stage(stage),
+                    );
+                }
+            }
+            Err(error) => result.record_failure(
+                Failure::new(definition, intended_send, actual_send, elapsed, error)
+                    .with_stage(stage),
+            ),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::load_test::feeder::{FeedOrder, OnExhausted};
+    use crate::load_test::profile::{LoadProfile, Stage};
+    use crate::load_test::result::Sample;
+    use crate::request::body::Body;
+    use crate::request::extract::Extractor;
     use crate::request::interface::HTTPClient;
     use crate::request::interface::TimedResponse;
-    use erased_serde::Serialize;
-    use serde_json::json;
-    use std::cell::RefCell;
+    use crate::statistics::summary::{Summary, SummaryRow};
+    use std::sync::Mutex;
     use std::time::Duration;
 
     struct TestHTTPClient {
-        post_request_endpoints: RefCell<Vec<(String, String)>>,
-        get_request_endpoints: RefCell<Vec<String>>,
+        post_request_endpoints: Mutex<Vec<(String, String)>>,
+        get_request_endpoints: Mutex<Vec<String>>,
     }
 
     impl TestHTTPClient {
         fn emtpy() -> Self {
             Self {
-                post_request_endpoints: RefCell::new(vec![]),
-                get_request_endpoints: RefCell::new(vec![]),
+                post_request_endpoints: Mutex::new(vec![]),
+                get_request_endpoints: Mutex::new(vec![]),
             }
         }
     }
     impl HTTPClient for TestHTTPClient {
-        fn get(
+        fn send(
             &self,
-            endpoint: &'_ str,
+            request: &ResolvedRequest<'_>,
         ) -> Result<TimedResponse, crate::request::interface::RequestError> {
-            let mut get_request_endpoints = self.get_request_endpoints.borrow_mut();
-            get_request_endpoints.push(endpoint.to_string());
+            match request.body() {
+                Some(Body::Json(body)) => {
+                    let mut post_request_endpoints = self.post_request_endpoints.lock().unwrap();
+                    post_request_endpoints.push((
+                        request.endpoint().to_string(),
+                        String::from_utf8_lossy(body).into_owned(),
+                    ));
 
-            Ok(TimedResponse::new(
-                "alive".to_string(),
-                Duration::from_millis(10),
-            ))
-        }
-        fn post<'a>(
-            &self,
-            endpoint: &'_ str,
-            body: &'a dyn Serialize,
-        ) -> Result<TimedResponse, crate::request::interface::RequestError> {
-            let mut post_request_endpoints = self.post_request_endpoints.borrow_mut();
-            post_request_endpoints.push((endpoint.to_string(), json!(body).to_string()));
+                    Ok(TimedResponse::new(
+                        "user created".to_string(),
+                        Duration::from_millis(50),
+                    ))
+                }
+                _ => {
+                    let mut get_request_endpoints = self.get_request_endpoints.lock().unwrap();
+                    get_request_endpoints.push(request.endpoint().to_string());
 
-            Ok(TimedResponse::new(
-                "user created".to_string(),
-                Duration::from_millis(50),
-            ))
+                    Ok(TimedResponse::new(
+                        "alive".to_string(),
+                        Duration::from_millis(10),
+                    ))
+                }
+            }
         }
     }
 
     #[derive(serde::Serialize)]
-    struct TestPayload<'a> {
-        name: &'a str,
+    struct TestPayload {
+        name: &'static str,
     }
 
     #[test]
     fn full_loadtest() {
-        let client = TestHTTPClient::emtpy();
+        let client = Arc::new(TestHTTPClient::emtpy());
 
         let steven = TestPayload { name: "Steven" };
         let sarah = TestPayload { name: "Sarah" };
 
         let load_test = LoadTest::new(
-            &client,
+            Arc::clone(&client),
             vec![
-                RequestDefinition::GET {
-                    endpoint: "/healthz",
-                },
-                RequestDefinition::POST {
-                    endpoint: "/add-user",
-                    to_json: &steven,
-                },
-                RequestDefinition::POST {
-                    endpoint: "/add-user",
-                    to_json: &sarah,
-                },
+                RequestDefinition::get("/healthz"),
+                RequestDefinition::post("/add-user", Body::json(&steven).unwrap()),
+                RequestDefinition::post("/add-user", Body::json(&sarah).unwrap()),
             ],
-        );
+        )
+        .with_retained_samples(true);
         let result = load_test.run();
 
         assert_eq!(
-            result,
-            vec! {
+            result
+                .iter()
+                .map(|user_result| user_result
+                    .samples()
+                    .iter()
+                    .map(|sample| sample.response().clone())
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec! {
                 TimedResponse::new("alive".to_string(), Duration::from_millis(10)),
                 TimedResponse::new("user created".to_string(), Duration::from_millis(50)),
                 TimedResponse::new("user created".to_string(), Duration::from_millis(50)),
-            }
+            }]
         );
 
         assert_eq!(
-            client.post_request_endpoints.into_inner(),
+            client.post_request_endpoints.lock().unwrap().clone(),
             vec![
                 (
                     String::from("/add-user"),
@@ -138,8 +627,856 @@ mod tests {
         );
 
         assert_eq!(
-            client.get_request_endpoints.into_inner(),
+            client.get_request_endpoints.lock().unwrap().clone(),
             vec![String::from("/healthz"),]
         );
     }
+
+    #[test]
+    fn load_test_runs_on_spawned_thread() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+        let endpoint = String::from("/healthz");
+        let load_test = LoadTest::new(Arc::clone(&client), vec![RequestDefinition::get(&endpoint)]);
+        drop(endpoint);
+
+        let result = thread::spawn(move || load_test.run()).join().unwrap();
+
+        assert_eq!(result[0].latencies()[0].count(), 1);
+        assert_eq!(
+            client.get_request_endpoints.lock().unwrap().clone(),
+            vec![String::from("/healthz")]
+        );
+    }
+
+    impl AsyncHTTPClient for TestHTTPClient {
+        async fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+            HTTPClient::send(self, request)
+        }
+    }
+
+    #[tokio::test]
+    async fn async_users_run_all_requests() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::get("/healthz"),
+                RequestDefinition::post(
+                    "/add-user",
+                    Body::json(&TestPayload { name: "Steven" }).unwrap(),
+                ),
+            ],
+        )
+        .with_virtual_users(3)
+        .with_run_mode(RunMode::Iterations(2));
+        let result = load_test.run_async().await;
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|user_result| (
+                    user_result.user(),
+                    user_result.latencies()[0].count(),
+                    user_result.latencies()[1].count()
+                ))
+                .collect::<Vec<_>>(),
+            vec![(0, 2, 2), (1, 2, 2), (2, 2, 2)]
+        );
+        assert_eq!(client.get_request_endpoints.lock().unwrap().len(), 6);
+        assert_eq!(client.post_request_endpoints.lock().unwrap().len(), 6);
+    }
+
+    struct AsyncSleepingHTTPClient {
+        delay: Duration,
+    }
+
+    impl AsyncHTTPClient for AsyncSleepingHTTPClient {
+        async fn send(
+            &self,
+            _request: &ResolvedRequest<'_>,
+        ) -> Result<TimedResponse, RequestError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(TimedResponse::new("alive".to_string(), self.delay))
+        }
+    }
+
+    #[derive(Default)]
+    struct InFlightHTTPClient {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl AsyncHTTPClient for InFlightHTTPClient {
+        async fn send(
+            &self,
+            _request: &ResolvedRequest<'_>,
+        ) -> Result<TimedResponse, RequestError> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(TimedResponse::new("alive".to_string(), Duration::ZERO))
+        }
+    }
+
+    #[tokio::test]
+    async fn async_users_do_not_need_a_thread_each() {
+        // The test runtime has a single thread and each request yields once, so the
+        // peak only reaches the number of users if they all share that thread.
+        let client = Arc::new(InFlightHTTPClient::default());
+
+        let load_test = LoadTest::new(client.clone(), vec![RequestDefinition::get("/healthz")])
+            .with_virtual_users(500);
+        let result = load_test.run_async().await;
+
+        assert_eq!(result.len(), 500);
+        assert!(result
+            .iter()
+            .all(|user_result| user_result.latencies()[0].count() == 1));
+        assert_eq!(client.peak.load(Ordering::SeqCst), 500);
+    }
+
+    #[tokio::test]
+    async fn async_arrival_rate() {
+        let client = Arc::new(AsyncSleepingHTTPClient {
+            delay: Duration::from_millis(50),
+        });
+
+        let load_test = LoadTest::new(client, vec![RequestDefinition::get("/healthz")])
+            .with_virtual_users(10)
+            .with_executor(Executor::constant_arrival_rate(100.0).unwrap())
+            .with_run_mode(RunMode::Iterations(10))
+            .with_retained_samples(true);
+        let result = load_test.run_async().await;
+
+        let mut intended: Vec<_> = result
+            .iter()
+            .flat_map(|user_result| user_result.samples())
+            .map(|sample| sample.intended_send())
+            .collect();
+        intended.sort();
+        assert_eq!(
+            intended,
+            (0..10)
+                .map(|index| Duration::from_millis(index * 10))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    struct PerUserHTTPClient {
+        user: Option<usize>,
+        senders: Arc<Mutex<Vec<Option<usize>>>>,
+    }
+
+    impl HTTPClient for PerUserHTTPClient {
+        fn send(&self, _request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+            self.senders.lock().unwrap().push(self.user);
+            Ok(TimedResponse::new("alive".to_string(), Duration::ZERO))
+        }
+
+        fn for_user(&self, user: usize) -> Option<Self> {
+            Some(Self {
+                user: Some(user),
+                senders: Arc::clone(&self.senders),
+            })
+        }
+    }
+
+    #[test]
+    fn virtual_users_send_through_their_own_client() {
+        let senders = Arc::new(Mutex::new(vec![]));
+        let client = Arc::new(PerUserHTTPClient {
+            user: None,
+            senders: Arc::clone(&senders),
+        });
+
+        LoadTest::new(client, vec![RequestDefinition::get("/healthz")])
+            .with_virtual_users(3)
+            .run();
+
+        let mut senders = senders.lock().unwrap().clone();
+        senders.sort();
+        assert_eq!(senders, vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn every_virtual_user_runs_all_requests() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let steven = TestPayload { name: "Steven" };
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::get("/healthz"),
+                RequestDefinition::post("/add-user", Body::json(&steven).unwrap()),
+            ],
+        )
+        .with_virtual_users(4)
+        .with_retained_samples(true);
+        let mut result = load_test.run();
+        result.sort_by_key(|user_result| user_result.user());
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|user_result| (user_result.user(), user_result.samples().len()))
+                .collect::<Vec<_>>(),
+            vec![(0, 2), (1, 2), (2, 2), (3, 2)]
+        );
+        assert_eq!(
+            client.get_request_endpoints.lock().unwrap().clone().len(),
+            4
+        );
+        assert_eq!(
+            client.post_request_endpoints.lock().unwrap().clone().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn repeats_scenario_for_iterations() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        )
+        .with_virtual_users(2)
+        .with_run_mode(RunMode::Iterations(3))
+        .with_retained_samples(true);
+        let result = load_test.run();
+
+        assert!(result
+            .iter()
+            .all(|user_result| user_result.samples().len() == 3));
+        assert_eq!(
+            client.get_request_endpoints.lock().unwrap().clone().len(),
+            6
+        );
+    }
+
+    #[test]
+    fn stops_after_duration() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        )
+        .with_run_mode(RunMode::Duration(Duration::from_millis(20)))
+        .with_retained_samples(true);
+        let started = Instant::now();
+        let result = load_test.run();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!result[0].samples().is_empty());
+        assert_eq!(
+            client.get_request_endpoints.lock().unwrap().clone().len(),
+            result[0].samples().len()
+        );
+    }
+
+    struct SleepingHTTPClient {
+        delay: Duration,
+    }
+
+    impl HTTPClient for SleepingHTTPClient {
+        fn send(
+            &self,
+            _request: &ResolvedRequest<'_>,
+        ) -> Result<TimedResponse, crate::request::interface::RequestError> {
+            std::thread::sleep(self.delay);
+            Ok(TimedResponse::new("alive".to_string(), self.delay))
+        }
+    }
+
+    #[test]
+    fn arrival_rate_schedules_requests_independent_of_responses() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::get("/healthz"),
+                RequestDefinition::get("/healthz"),
+            ],
+        )
+        .with_virtual_users(2)
+        .with_run_mode(RunMode::Iterations(2))
+        .with_executor(Executor::constant_arrival_rate(100.0).unwrap())
+        .with_retained_samples(true);
+        let result = load_test.run();
+
+        let mut intended_sends: Vec<_> = result
+            .iter()
+            .flat_map(|user_result| user_result.samples())
+            .map(|sample| sample.intended_send())
+            .collect();
+        intended_sends.sort();
+
+        assert_eq!(
+            intended_sends,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+                Duration::from_millis(30)
+            ]
+        );
+        assert_eq!(
+            client.get_request_endpoints.lock().unwrap().clone().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn arrival_rate_latency_includes_queueing() {
+        let client = Arc::new(SleepingHTTPClient {
+            delay: Duration::from_millis(50),
+        });
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        )
+        .with_run_mode(RunMode::Iterations(3))
+        .with_executor(Executor::constant_arrival_rate(100.0).unwrap())
+        .with_retained_samples(true);
+        let result = load_test.run();
+
+        let last_sample = result[0].samples().last().unwrap();
+        assert_eq!(last_sample.intended_send(), Duration::from_millis(20));
+        assert!(last_sample.actual_send() >= Duration::from_millis(100));
+        assert!(last_sample.latency() >= Duration::from_millis(130));
+    }
+
+    #[test]
+    fn arrival_rate_stops_after_duration() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        )
+        .with_run_mode(RunMode::Duration(Duration::from_millis(55)))
+        .with_executor(Executor::constant_arrival_rate(100.0).unwrap())
+        .with_retained_samples(true);
+        let result = load_test.run();
+
+        assert_eq!(result[0].samples().len(), 6);
+    }
+
+    #[test]
+    fn profile_ramps_users_and_reports_stages() {
+        // Stages are far longer than a thread takes to start on a loaded machine, so each
+        // of them sees requests.
+        let client = Arc::new(SleepingHTTPClient {
+            delay: Duration::from_millis(10),
+        });
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        )
+        .with_run_mode(RunMode::Profile(LoadProfile::new(
+            1,
+            vec![
+                Stage::new(Duration::from_millis(500), 1),
+                Stage::new(Duration::from_millis(500), 3),
+            ],
+        )));
+        let mut result = load_test.run();
+        result.sort_by_key(|user_result| user_result.user());
+        let summary = Summary::new(load_test.request_definitions(), &result).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert!(result[2]
+            .stage_latencies()
+            .keys()
+            .all(|(stage, _)| *stage == 1));
+        assert_eq!(
+            summary
+                .per_stage()
+                .iter()
+                .map(SummaryRow::name)
+                .collect::<Vec<_>>(),
+            vec!["stage 0", "stage 1"]
+        );
+        assert_eq!(
+            summary
+                .per_stage()
+                .iter()
+                .map(|row| row.latencies().count())
+                .sum::<u64>(),
+            summary.per_definition()[0].latencies().count()
+        );
+    }
+
+    #[test]
+    fn records_latencies_without_retaining_samples() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::get("/healthz"),
+                RequestDefinition::get("/healthz"),
+            ],
+        )
+        .with_run_mode(RunMode::Iterations(5));
+        let result = load_test.run();
+
+        assert!(result[0].samples().is_empty());
+        assert_eq!(
+            result[0]
+                .latencies()
+                .iter()
+                .map(|latencies| latencies.count())
+                .collect::<Vec<_>>(),
+            vec![5, 5]
+        );
+    }
+
+    struct FailingHTTPClient;
+
+    impl HTTPClient for FailingHTTPClient {
+        fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+            match request.method() {
+                &http::Method::GET => Err(RequestError::RequestUnsuccesful),
+                _ => Ok(TimedResponse::new(
+                    "user created".to_string(),
+                    Duration::from_millis(50),
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn records_failures_with_their_definition() {
+        let client = Arc::new(FailingHTTPClient);
+        let steven = TestPayload { name: "Steven" };
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::post("/add-user", Body::json(&steven).unwrap()),
+                RequestDefinition::get("/healthz"),
+            ],
+        )
+        .with_run_mode(RunMode::Iterations(2));
+        let result = load_test.run();
+
+        assert_eq!(result[0].latencies()[0].count(), 2);
+        assert_eq!(
+            result[0]
+                .failures()
+                .iter()
+                .map(|failure| (failure.definition(), failure.error().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, RequestError::RequestUnsuccesful),
+                (1, RequestError::RequestUnsuccesful)
+            ]
+        );
+    }
+
+    struct StatusHTTPClient {
+        status: http::StatusCode,
+    }
+
+    impl HTTPClient for StatusHTTPClient {
+        fn send(&self, _request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+            Ok(
+                TimedResponse::new("status".to_string(), Duration::from_millis(5))
+                    .with_status(self.status),
+            )
+        }
+    }
+
+    #[test]
+    fn classifies_responses_by_status_class() {
+        let client = Arc::new(StatusHTTPClient {
+            status: http::StatusCode::SERVICE_UNAVAILABLE,
+        });
+
+        let load_test = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz")],
+        );
+        let result = load_test.run();
+
+        assert_eq!(
+            result[0].status_classes()[0],
+            std::collections::BTreeMap::from([(
+                crate::request::interface::StatusClass::ServerError,
+                1
+            )])
+        );
+        assert!(result[0].latencies()[0].is_empty());
+        assert_eq!(result[0].failures()[0].error(), &RequestError::Status(503));
+    }
+
+    #[test]
+    fn counts_checks() {
+        let client = Arc::new(StatusHTTPClient {
+            status: http::StatusCode::OK,
+        });
+        let failing_client = Arc::new(FailingHTTPClient);
+        let checks = || {
+            vec![
+                crate::request::check::Check::Status(http::StatusCode::OK),
+                crate::request::check::Check::BodyContains(String::from("alive")),
+            ]
+        };
+
+        let passing = LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/healthz").with_checks(checks())],
+        )
+        .with_run_mode(RunMode::Iterations(2))
+        .run();
+        let failing = LoadTest::new(
+            Arc::clone(&failing_client),
+            vec![RequestDefinition::get("/healthz").with_checks(checks())],
+        )
+        .run();
+
+        let counts = |result: &UserResult| {
+            result.checks()[0]
+                .iter()
+                .map(|count| (count.passes(), count.fails()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(counts(&passing[0]), vec![(2, 0), (0, 2)]);
+        assert_eq!(counts(&failing[0]), vec![(0, 1), (0, 1)]);
+    }
+
+    fn actual_sends(result: &UserResult) -> Vec<Duration> {
+        result.samples().iter().map(Sample::actual_send).collect()
+    }
+
+    #[test]
+    fn think_time_between_requests() {
+        let load_test = LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![
+                RequestDefinition::get("/alive"),
+                RequestDefinition::get("/alive")
+                    .with_think_time(ThinkTime::Constant(Duration::ZERO)),
+                RequestDefinition::get("/alive"),
+            ],
+        )
+        .with_think_time(ThinkTime::Constant(Duration::from_millis(50)))
+        .with_retained_samples(true);
+
+        let sends = actual_sends(&load_test.run()[0]);
+
+        assert!(sends[1] - sends[0] >= Duration::from_millis(50));
+        assert!(sends[2] - sends[1] < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn pacing_spaces_iteration_starts() {
+        let load_test = LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![RequestDefinition::get("/alive")],
+        )
+        .with_run_mode(RunMode::Iterations(3))
+        .with_pacing(Duration::from_millis(40))
+        .with_retained_samples(true);
+
+        let sends = actual_sends(&load_test.run()[0]);
+
+        assert!(sends[1] >= Duration::from_millis(40));
+        assert!(sends[2] >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn iteration_think_time_after_pacing_interval() {
+        let load_test = LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![RequestDefinition::get("/alive")],
+        )
+        .with_run_mode(RunMode::Iterations(2))
+        .with_pacing(Duration::from_millis(10))
+        .with_iteration_think_time(ThinkTime::Constant(Duration::from_millis(60)))
+        .with_retained_samples(true);
+
+        let sends = actual_sends(&load_test.run()[0]);
+
+        assert!(sends[1] - sends[0] >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn think_time_ends_with_duration() {
+        let load_test = LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![RequestDefinition::get("/alive")],
+        )
+        .with_run_mode(RunMode::Duration(Duration::from_millis(50)))
+        .with_think_time(ThinkTime::Constant(Duration::from_secs(60)));
+        let started = Instant::now();
+
+        let result = load_test.run();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(result[0].latencies()[0].count(), 1);
+    }
+
+    #[tokio::test]
+    async fn async_think_time_between_requests() {
+        let load_test = LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![
+                RequestDefinition::get("/alive"),
+                RequestDefinition::get("/alive"),
+            ],
+        )
+        .with_think_time(ThinkTime::Uniform {
+            min: Duration::from_millis(30),
+            max: Duration::from_millis(40),
+        })
+        .with_retained_samples(true);
+
+        let sends = actual_sends(&load_test.run_async().await[0]);
+
+        assert!(sends[1] - sends[0] >= Duration::from_millis(30));
+    }
+
+    fn weighted_load_test() -> LoadTest<TestHTTPClient> {
+        LoadTest::new(
+            Arc::new(TestHTTPClient::emtpy()),
+            vec![
+                RequestDefinition::get("/alive").with_weight(70),
+                RequestDefinition::get("/tsp/six").with_weight(20),
+                RequestDefinition::get("/tsp/twenty-nine").with_weight(10),
+            ],
+        )
+        .with_virtual_users(2)
+        .with_run_mode(RunMode::Iterations(1_000))
+        .with_request_mix(RequestMix::Weighted)
+        .with_retained_samples(true)
+    }
+
+    fn picked_definitions(results: &[UserResult]) -> Vec<Vec<usize>> {
+        results
+            .iter()
+            .map(|result| result.samples().iter().map(Sample::definition).collect())
+            .collect()
+    }
+
+    #[test]
+    fn weighted_mix_sends_one_drawn_request_per_iteration() {
+        let result = weighted_load_test().run();
+
+        let counts: Vec<_> = result[0]
+            .latencies()
+            .iter()
+            .map(LatencyRecorder::count)
+            .collect();
+        assert_eq!(counts.iter().sum::<u64>(), 1_000);
+        assert!((620..780).contains(&counts[0]), "{:?}", counts);
+        assert!((140..260).contains(&counts[1]), "{:?}", counts);
+        assert!((50..150).contains(&counts[2]), "{:?}", counts);
+    }
+
+    #[test]
+    fn seeded_mix_repeats() {
+        let first = weighted_load_test().with_seed(42).run();
+        let second = weighted_load_test().with_seed(42).run();
+        let other_seed = weighted_load_test().with_seed(43).run();
+
+        assert_eq!(picked_definitions(&first), picked_definitions(&second));
+        assert_ne!(picked_definitions(&first), picked_definitions(&other_seed));
+        assert_ne!(picked_definitions(&first)[0], picked_definitions(&first)[1]);
+    }
+
+    #[test]
+    fn weighted_mix_with_arrival_rate() {
+        let result = weighted_load_test()
+            .with_virtual_users(1)
+            .with_run_mode(RunMode::Iterations(20))
+            .with_executor(Executor::constant_arrival_rate(1_000.0).unwrap())
+            .run();
+
+        assert_eq!(result[0].samples().len(), 20);
+    }
+
+    #[derive(Default)]
+    struct JobsHTTPClient {
+        created: Mutex<usize>,
+        polled: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    impl HTTPClient for JobsHTTPClient {
+        fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+            if request.method() == http::Method::POST {
+                let mut created = self.created.lock().unwrap();
+                *created += 1;
+                return Ok(TimedResponse::new(
+                    format!(r#"{{"id": "job-{}"}}"#, created),
+                    Duration::ZERO,
+                ));
+            }
+            let job = request
+                .headers()
+                .iter()
+                .find(|(name, _)| name == "x-job")
+                .map(|(_, value)| value.clone());
+            self.polled
+                .lock()
+                .unwrap()
+                .push((request.endpoint().to_string(), job));
+            Ok(TimedResponse::new(String::from("{}"), Duration::ZERO))
+        }
+    }
+
+    #[test]
+    fn extracted_values_feed_later_requests() {
+        let client = Arc::new(JobsHTTPClient::default());
+
+        LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::post("/tsp/jobs", Body::text("six cities"))
+                    .with_extraction("job", Extractor::JsonPointer(String::from("/id"))),
+                RequestDefinition::get("/tsp/jobs/{{job}}").with_header("x-job", "{{job}}"),
+            ],
+        )
+        .with_run_mode(RunMode::Iterations(2))
+        .run();
+
+        assert_eq!(
+            client.polled.lock().unwrap().clone(),
+            vec![
+                (String::from("/tsp/jobs/job-1"), Some(String::from("job-1"))),
+                (String::from("/tsp/jobs/job-2"), Some(String::from("job-2"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn arrival_rate_workers_run_whole_iterations() {
+        let client = Arc::new(JobsHTTPClient::default());
+
+        LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::post("/tsp/jobs", Body::text("six cities"))
+                    .with_extraction("job", Extractor::JsonPointer(String::from("/id"))),
+                RequestDefinition::get("/tsp/jobs/{{job}}").with_header("x-job", "{{job}}"),
+            ],
+        )
+        .with_virtual_users(3)
+        .with_run_mode(RunMode::Iterations(6))
+        .with_executor(Executor::constant_arrival_rate(1_000.0).unwrap())
+        .run();
+
+        let mut polled = client.polled.lock().unwrap().clone();
+        polled.sort();
+        assert_eq!(
+            polled,
+            (1..=6)
+                .map(|job| (
+                    format!("/tsp/jobs/job-{}", job),
+                    Some(format!("job-{}", job))
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn variables_belong_to_one_user() {
+        let client = Arc::new(JobsHTTPClient::default());
+
+        LoadTest::new(
+            Arc::clone(&client),
+            vec![
+                RequestDefinition::post("/tsp/jobs", Body::text("six cities"))
+                    .with_extraction("job", Extractor::JsonPointer(String::from("/id"))),
+                RequestDefinition::get("/tsp/jobs/{{job}}"),
+            ],
+        )
+        .with_virtual_users(8)
+        .run();
+
+        let mut polled: Vec<_> = client
+            .polled
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(endpoint, _)| endpoint.clone())
+            .collect();
+        polled.sort();
+        polled.dedup();
+        assert_eq!(polled.len(), 8);
+    }
+
+    #[test]
+    fn feeder_rows_fill_requests_until_they_run_out() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+        let feeder = Feeder::from_jsonl(
+            "{\"city\": \"berlin\", \"distances\": [[0, 1], [1, 0]]}\n{\"city\": \"paris\", \"distances\": [[0]]}",
+        )
+        .unwrap()
+        .with_on_exhausted(OnExhausted::Stop);
+
+        LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::post(
+                "/tsp/{{city}}",
+                Body::json_template(r#"{"distances": {{distances}}}"#),
+            )],
+        )
+        .with_run_mode(RunMode::Iterations(5))
+        .with_feeder(feeder)
+        .run();
+
+        assert_eq!(
+            client.post_request_endpoints.lock().unwrap().clone(),
+            vec![
+                (
+                    String::from("/tsp/berlin"),
+                    String::from(r#"{"distances": [[0,1],[1,0]]}"#)
+                ),
+                (
+                    String::from("/tsp/paris"),
+                    String::from(r#"{"distances": [[0]]}"#)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unique_feeder_rows_per_user() {
+        let client = Arc::new(TestHTTPClient::emtpy());
+        let feeder = Feeder::from_csv("city\nberlin\nparis\nrome\n")
+            .unwrap()
+            .with_order(FeedOrder::UniquePerUser);
+
+        LoadTest::new(
+            Arc::clone(&client),
+            vec![RequestDefinition::get("/cities/{{city}}")],
+        )
+        .with_virtual_users(5)
+        .with_run_mode(RunMode::Iterations(2))
+        .with_feeder(feeder)
+        .run();
+
+        let mut endpoints = client.get_request_endpoints.lock().unwrap().clone();
+        endpoints.sort();
+        assert_eq!(
+            endpoints,
+            [
+                "/cities/berlin",
+                "/cities/berlin",
+                "/cities/paris",
+                "/cities/paris",
+                "/cities/rome",
+                "/cities/rome"
+            ]
+        );
+    }
 }
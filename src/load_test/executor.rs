@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Executor {
+    #[default]
+    ClosedLoop,
+    ConstantArrivalRate(ArrivalRate),
+}
+
+impl Executor {
+    pub fn constant_arrival_rate(requests_per_second: f64) -> Result<Self, ExecutorError> {
+        ArrivalRate::new(requests_per_second).map(Executor::ConstantArrivalRate)
+    }
+}
+
+/// A number of requests per second that can space requests, so finite and above zero.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "ArrivalRateSpec")]
+pub struct ArrivalRate {
+    requests_per_second: f64,
+}
+
+impl ArrivalRate {
+    fn new(requests_per_second: f64) -> Result<Self, ExecutorError> {
+        if requests_per_second.is_finite() && requests_per_second > 0.0 {
+            Ok(Self {
+                requests_per_second,
+            })
+        } else {
+            Err(ExecutorError::InvalidRate(requests_per_second))
+        }
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    pub(crate) fn intended_send(&self, request_index: usize) -> Duration {
+        Duration::from_secs_f64(request_index as f64 / self.requests_per_second)
+    }
+}
+
+#[derive(Deserialize)]
+struct ArrivalRateSpec {
+    requests_per_second: f64,
+}
+
+impl TryFrom<ArrivalRateSpec> for ArrivalRate {
+    type Error = ExecutorError;
+
+    fn try_from(spec: ArrivalRateSpec) -> Result<Self, Self::Error> {
+        ArrivalRate::new(spec.requests_per_second)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutorError {
+    InvalidRate(f64),
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutorError::InvalidRate(rate) => write!(
+                f,
+                "invalid rate {}, expected a finite number of requests per second above zero",
+                rate
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rate(requests_per_second: f64) -> ArrivalRate {
+        ArrivalRate::new(requests_per_second).unwrap()
+    }
+
+    #[test]
+    fn first_request_is_sent_immediately() {
+        assert_eq!(rate(10.0).intended_send(0), Duration::ZERO);
+    }
+
+    #[test]
+    fn requests_are_spaced_by_rate() {
+        assert_eq!(rate(10.0).intended_send(1), Duration::from_millis(100));
+        assert_eq!(rate(4.0).intended_send(6), Duration::from_millis(1_500));
+    }
+
+    #[test]
+    fn rejects_rates_that_cannot_space_requests() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                Executor::constant_arrival_rate(rate),
+                Err(ExecutorError::InvalidRate(_))
+            ));
+        }
+        assert_eq!(
+            Executor::constant_arrival_rate(0.5),
+            Ok(Executor::ConstantArrivalRate(rate(0.5)))
+        );
+        assert_eq!(rate(0.5).requests_per_second(), 0.5);
+    }
+
+    #[test]
+    fn rates_are_checked_when_deserialized() {
+        let executor = |rate: &str| {
+            serde_json::from_str::<Executor>(&format!(
+                r#"{{"constant_arrival_rate": {{"requests_per_second": {}}}}}"#,
+                rate
+            ))
+        };
+
+        assert_eq!(
+            executor("20").unwrap(),
+            Executor::ConstantArrivalRate(rate(20.0))
+        );
+        assert!(executor("0").is_err());
+        assert!(executor("-5").is_err());
+    }
+
+    #[test]
+    fn default_is_closed_loop() {
+        assert_eq!(Executor::default(), Executor::ClosedLoop);
+    }
+}
@@ -0,0 +1,304 @@
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Values of one record, by column name.
+pub type Row = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedOrder {
+    /// Every iteration takes the next row, counted across all virtual users.
+    #[default]
+    Sequential,
+    /// Every iteration draws a row with the user's random number generator.
+    Random,
+    /// Every virtual user keeps the row at its own index for the whole run. Users without
+    /// a row of their own stop whatever `OnExhausted` says, so no two users share a row.
+    UniquePerUser,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnExhausted {
+    /// Starts over at the first row.
+    #[default]
+    Recycle,
+    /// Ends a virtual user once there is no row left for it.
+    Stop,
+}
+
+/// Rows, usually read from a CSV or JSONL file, whose columns virtual users get as
+/// variables at the start of every iteration.
+#[derive(Debug)]
+pub struct Feeder {
+    rows: Vec<Row>,
+    order: FeedOrder,
+    on_exhausted: OnExhausted,
+    next_row: AtomicUsize,
+}
+
+impl Feeder {
+    pub fn new(rows: Vec<Row>) -> Self {
+        Self {
+            rows,
+            order: FeedOrder::default(),
+            on_exhausted: OnExhausted::default(),
+            next_row: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reads `.csv`, `.jsonl` or `.ndjson` files.
+    pub fn from_path(path: &Path) -> Result<Self, FeederError> {
+        let parse = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Self::from_csv,
+            Some("jsonl" | "ndjson") => Self::from_jsonl,
+            _ => return Err(FeederError::UnsupportedFormat(path.to_path_buf())),
+        };
+        let contents = fs::read_to_string(path).map_err(|error| FeederError::Read {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })?;
+        parse(&contents)
+    }
+
+    /// The header line names the columns.
+    pub fn from_csv(contents: &str) -> Result<Self, FeederError> {
+        let mut reader = csv::Reader::from_reader(contents.as_bytes());
+        let headers = reader.headers().map_err(csv_error)?.clone();
+        let rows = reader
+            .records()
+            .map(|record| {
+                record.map(|record| {
+                    headers
+                        .iter()
+                        .zip(record.iter())
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect()
+                })
+            })
+            .collect::<Result<Vec<Row>, _>>()
+            .map_err(csv_error)?;
+        Self::with_rows(rows)
+    }
+
+    /// Every line holds a JSON object. String values are taken as they are, other values
+    /// as JSON, so they can stand for whole values in JSON bodies.
+    pub fn from_jsonl(contents: &str) -> Result<Self, FeederError> {
+        let rows = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(line)
+                    .map_err(|error| {
+                        FeederError::Parse(format!("line {}: {}", index + 1, error))
+                    })?;
+                Ok(object
+                    .into_iter()
+                    .map(|(name, value)| match value {
+                        serde_json::Value::String(value) => (name, value),
+                        value => (name, value.to_string()),
+                    })
+                    .collect())
+            })
+            .collect::<Result<Vec<Row>, FeederError>>()?;
+        Self::with_rows(rows)
+    }
+
+    fn with_rows(rows: Vec<Row>) -> Result<Self, FeederError> {
+        if rows.is_empty() {
+            return Err(FeederError::Parse(String::from("no rows")));
+        }
+        Ok(Self::new(rows))
+    }
+
+    pub fn with_order(mut self, order: FeedOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_on_exhausted(mut self, on_exhausted: OnExhausted) -> Self {
+        self.on_exhausted = on_exhausted;
+        self
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn order(&self) -> FeedOrder {
+        self.order
+    }
+
+    pub fn on_exhausted(&self) -> OnExhausted {
+        self.on_exhausted
+    }
+
+    /// `None` once the feeder stops handing out rows to `user`.
+    pub(crate) fn next_row<R: Rng + ?Sized>(&self, user: usize, rng: &mut R) -> Option<&Row> {
+        let index = match self.order {
+            FeedOrder::Sequential => self.next_row.fetch_add(1, Ordering::Relaxed),
+            FeedOrder::Random if self.rows.is_empty() => return None,
+            FeedOrder::Random => rng.gen_range(0..self.rows.len()),
+            FeedOrder::UniquePerUser => return self.rows.get(user),
+        };
+        match self.on_exhausted {
+            OnExhausted::Recycle => index
+                .checked_rem(self.rows.len())
+                .map(|index| &self.rows[index]),
+            OnExhausted::Stop => self.rows.get(index),
+        }
+    }
+}
+
+fn csv_error(error: csv::Error) -> FeederError {
+    FeederError::Parse(error.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeederError {
+    Read { path: PathBuf, message: String },
+    UnsupportedFormat(PathBuf),
+    Parse(String),
+}
+
+impl fmt::Display for FeederError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeederError::Read { path, message } => {
+                write!(f, "could not read '{}': {}", path.display(), message)
+            }
+            FeederError::UnsupportedFormat(path) => write!(
+                f,
+                "unsupported feeder format '{}', expected .csv, .jsonl or .ndjson",
+                path.display()
+            ),
+            FeederError::Parse(message) => write!(f, "invalid feeder: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FeederError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn cities(feeder: &Feeder, user: usize, rows: usize) -> Vec<Option<String>> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..rows)
+            .map(|_| {
+                feeder
+                    .next_row(user, &mut rng)
+                    .map(|row| row["city"].clone())
+            })
+            .collect()
+    }
+
+    fn three_cities() -> Feeder {
+        Feeder::from_csv("city\nBerlin\nParis\nRome\n").unwrap()
+    }
+
+    #[test]
+    fn rows_from_csv() {
+        let feeder = Feeder::from_csv("city,population\n\"Berlin, DE\",3.6\nParis,2.1\n").unwrap();
+
+        assert_eq!(
+            feeder.rows(),
+            [
+                Row::from([
+                    (String::from("city"), String::from("Berlin, DE")),
+                    (String::from("population"), String::from("3.6")),
+                ]),
+                Row::from([
+                    (String::from("city"), String::from("Paris")),
+                    (String::from("population"), String::from("2.1")),
+                ]),
+            ]
+        );
+        assert!(matches!(
+            Feeder::from_csv("city,population\nBerlin\n"),
+            Err(FeederError::Parse(_))
+        ));
+        assert!(matches!(
+            Feeder::from_csv("city\n"),
+            Err(FeederError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn rows_from_jsonl() {
+        let feeder = Feeder::from_jsonl(
+            "{\"city\": \"Berlin\", \"distances\": [[0, 1], [1, 0]]}\n\n{\"city\": \"Paris\", \"size\": 2}\n",
+        )
+        .unwrap();
+
+        assert_eq!(feeder.rows().len(), 2);
+        assert_eq!(feeder.rows()[0]["distances"], "[[0,1],[1,0]]");
+        assert_eq!(feeder.rows()[1]["size"], "2");
+        assert_eq!(
+            Feeder::from_jsonl("{\"city\": \"Berlin\"}\n[1, 2]\n")
+                .unwrap_err()
+                .to_string(),
+            "invalid feeder: line 2: invalid type: sequence, expected a map at line 1 column 0"
+        );
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            Feeder::from_path(Path::new("cities.xlsx")).unwrap_err(),
+            FeederError::UnsupportedFormat(PathBuf::from("cities.xlsx"))
+        );
+        assert!(matches!(
+            Feeder::from_path(Path::new("/nonexistent/cities.jsonl")),
+            Err(FeederError::Read { .. })
+        ));
+    }
+
+    #[test]
+    fn sequential_rows_recycle_or_stop() {
+        let berlin = Some(String::from("Berlin"));
+        let paris = Some(String::from("Paris"));
+        let rome = Some(String::from("Rome"));
+
+        assert_eq!(
+            cities(&three_cities(), 0, 4),
+            [berlin.clone(), paris.clone(), rome.clone(), berlin.clone()]
+        );
+        let feeder = three_cities().with_on_exhausted(OnExhausted::Stop);
+        assert_eq!(cities(&feeder, 0, 2), [berlin, paris]);
+        assert_eq!(cities(&feeder, 1, 2), [rome, None]);
+    }
+
+    #[test]
+    fn unique_rows_per_user() {
+        let feeder = three_cities().with_order(FeedOrder::UniquePerUser);
+
+        assert_eq!(cities(&feeder, 1, 2), vec![Some(String::from("Paris")); 2]);
+        assert_eq!(cities(&feeder, 3, 1), [None]);
+        assert_eq!(
+            cities(&feeder.with_on_exhausted(OnExhausted::Stop), 3, 1),
+            [None]
+        );
+    }
+
+    #[test]
+    fn random_rows_never_run_out() {
+        let feeder = three_cities()
+            .with_order(FeedOrder::Random)
+            .with_on_exhausted(OnExhausted::Stop);
+        let drawn = cities(&feeder, 0, 30);
+
+        assert!(drawn.iter().all(Option::is_some));
+        assert!(drawn.contains(&Some(String::from("Rome"))));
+        assert_eq!(drawn, cities(&feeder, 0, 30));
+    }
+}
@@ -1 +1,8 @@
 pub mod core;
+pub mod executor;
+pub mod feeder;
+pub mod profile;
+pub mod request_mix;
+pub mod result;
+pub mod run_mode;
+pub mod think_time;
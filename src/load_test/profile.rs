@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage {
+    duration: Duration,
+    target_users: usize,
+}
+
+impl Stage {
+    pub fn new(duration: Duration, target_users: usize) -> Self {
+        Self {
+            duration,
+            target_users,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn target_users(&self) -> usize {
+        self.target_users
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadProfile {
+    start_users: usize,
+    stages: Vec<Stage>,
+}
+
+impl LoadProfile {
+    pub fn new(start_users: usize, stages: Vec<Stage>) -> Self {
+        Self {
+            start_users,
+            stages,
+        }
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.stages.iter().map(|stage| stage.duration).sum()
+    }
+
+    pub fn max_users(&self) -> usize {
+        self.stages
+            .iter()
+            .map(|stage| stage.target_users)
+            .fold(self.start_users, usize::max)
+    }
+
+    pub fn stage_at(&self, elapsed: Duration) -> Option<usize> {
+        let mut stage_start = Duration::ZERO;
+        for (index, stage) in self.stages.iter().enumerate() {
+            if elapsed < stage_start + stage.duration {
+                return Some(index);
+            }
+            stage_start += stage.duration;
+        }
+        None
+    }
+
+    pub fn users_at(&self, elapsed: Duration) -> usize {
+        let mut stage_start = Duration::ZERO;
+        let mut from_users = self.start_users;
+        for stage in &self.stages {
+            if elapsed < stage_start + stage.duration {
+                let progress = (elapsed - stage_start).as_secs_f64() / stage.duration.as_secs_f64();
+                let users =
+                    from_users as f64 + (stage.target_users as f64 - from_users as f64) * progress;
+                return users.round() as usize;
+            }
+            stage_start += stage.duration;
+            from_users = stage.target_users;
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ramp_hold_ramp_down() -> LoadProfile {
+        LoadProfile::new(
+            1,
+            vec![
+                Stage::new(Duration::from_secs(10), 11),
+                Stage::new(Duration::from_secs(20), 11),
+                Stage::new(Duration::from_secs(10), 0),
+            ],
+        )
+    }
+
+    #[test]
+    fn total_duration_sums_stages() {
+        assert_eq!(
+            ramp_hold_ramp_down().total_duration(),
+            Duration::from_secs(40)
+        );
+    }
+
+    #[test]
+    fn max_users_over_all_stages() {
+        assert_eq!(ramp_hold_ramp_down().max_users(), 11);
+    }
+
+    #[test]
+    fn users_ramp_linearly() {
+        let profile = ramp_hold_ramp_down();
+
+        assert_eq!(profile.users_at(Duration::ZERO), 1);
+        assert_eq!(profile.users_at(Duration::from_secs(5)), 6);
+        assert_eq!(profile.users_at(Duration::from_secs(15)), 11);
+        assert_eq!(profile.users_at(Duration::from_secs(35)), 6);
+        assert_eq!(profile.users_at(Duration::from_secs(40)), 0);
+    }
+
+    #[test]
+    fn stage_lookup() {
+        let profile = ramp_hold_ramp_down();
+
+        assert_eq!(profile.stage_at(Duration::ZERO), Some(0));
+        assert_eq!(profile.stage_at(Duration::from_secs(10)), Some(1));
+        assert_eq!(profile.stage_at(Duration::from_secs(39)), Some(2));
+        assert_eq!(profile.stage_at(Duration::from_secs(40)), None);
+    }
+}
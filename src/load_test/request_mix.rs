@@ -0,0 +1,100 @@
+use crate::request::definition::RequestDefinition;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestMix {
+    /// Every iteration sends all request definitions in order.
+    #[default]
+    Sequential,
+    /// Every iteration sends one request definition, drawn by weight.
+    Weighted,
+}
+
+pub(crate) enum RequestPicker {
+    Sequential(usize),
+    Weighted(WeightedIndex<u32>),
+}
+
+impl RequestPicker {
+    /// `None` if the mix can never pick a request.
+    pub(crate) fn new(mix: RequestMix, definitions: &[RequestDefinition]) -> Option<Self> {
+        if definitions.is_empty() {
+            return None;
+        }
+        match mix {
+            RequestMix::Sequential => Some(RequestPicker::Sequential(definitions.len())),
+            RequestMix::Weighted => {
+                WeightedIndex::new(definitions.iter().map(RequestDefinition::weight))
+                    .ok()
+                    .map(RequestPicker::Weighted)
+            }
+        }
+    }
+
+    pub(crate) fn requests_per_iteration(&self) -> usize {
+        match self {
+            RequestPicker::Sequential(definitions) => *definitions,
+            RequestPicker::Weighted(_) => 1,
+        }
+    }
+
+    pub(crate) fn pick<R: Rng + ?Sized>(&self, step: usize, rng: &mut R) -> usize {
+        match self {
+            RequestPicker::Sequential(_) => step,
+            RequestPicker::Weighted(weights) => weights.sample(rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn definitions(weights: &[u32]) -> Vec<RequestDefinition> {
+        weights
+            .iter()
+            .map(|weight| RequestDefinition::get("/alive").with_weight(*weight))
+            .collect()
+    }
+
+    #[test]
+    fn sequential_picks_in_order() {
+        let picker = RequestPicker::new(RequestMix::Sequential, &definitions(&[1, 1, 1])).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(picker.requests_per_iteration(), 3);
+        assert_eq!(
+            (0..3)
+                .map(|step| picker.pick(step, &mut rng))
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn weighted_follows_weights() {
+        let picker = RequestPicker::new(RequestMix::Weighted, &definitions(&[70, 20, 10])).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = [0; 3];
+
+        for _ in 0..10_000 {
+            counts[picker.pick(0, &mut rng)] += 1;
+        }
+
+        assert_eq!(picker.requests_per_iteration(), 1);
+        assert!((6_700..7_300).contains(&counts[0]), "{:?}", counts);
+        assert!((1_700..2_300).contains(&counts[1]), "{:?}", counts);
+        assert!((700..1_300).contains(&counts[2]), "{:?}", counts);
+    }
+
+    #[test]
+    fn nothing_to_pick() {
+        assert!(RequestPicker::new(RequestMix::Sequential, &[]).is_none());
+        assert!(RequestPicker::new(RequestMix::Weighted, &definitions(&[0, 0])).is_none());
+    }
+}
@@ -0,0 +1,419 @@
+use crate::request::interface::{Phase, RequestError, StatusClass, TimedResponse};
+use crate::statistics::histogram::LatencyRecorder;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    definition: usize,
+    stage: Option<usize>,
+    intended_send: Duration,
+    actual_send: Duration,
+    response: TimedResponse,
+}
+
+impl Sample {
+    pub fn new(
+        definition: usize,
+        intended_send: Duration,
+        actual_send: Duration,
+        response: TimedResponse,
+    ) -> Self {
+        Self {
+            definition,
+            stage: None,
+            intended_send,
+            actual_send,
+            response,
+        }
+    }
+
+    pub fn with_stage(mut self, stage: Option<usize>) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    pub fn definition(&self) -> usize {
+        self.definition
+    }
+
+    pub fn stage(&self) -> Option<usize> {
+        self.stage
+    }
+
+    pub fn intended_send(&self) -> Duration {
+        self.intended_send
+    }
+
+    pub fn actual_send(&self) -> Duration {
+        self.actual_send
+    }
+
+    pub fn response(&self) -> &TimedResponse {
+        &self.response
+    }
+
+    pub fn send_delay(&self) -> Duration {
+        self.actual_send.saturating_sub(self.intended_send)
+    }
+
+    pub fn latency(&self) -> Duration {
+        self.send_delay() + self.response.response_time()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    definition: usize,
+    stage: Option<usize>,
+    intended_send: Duration,
+    actual_send: Duration,
+    elapsed: Duration,
+    error: RequestError,
+}
+
+impl Failure {
+    pub fn new(
+        definition: usize,
+        intended_send: Duration,
+        actual_send: Duration,
+        elapsed: Duration,
+        error: RequestError,
+    ) -> Self {
+        Self {
+            definition,
+            stage: None,
+            intended_send,
+            actual_send,
+            elapsed,
+            error,
+        }
+    }
+
+    pub fn with_stage(mut self, stage: Option<usize>) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    pub fn definition(&self) -> usize {
+        self.definition
+    }
+
+    pub fn stage(&self) -> Option<usize> {
+        self.stage
+    }
+
+    pub fn intended_send(&self) -> Duration {
+        self.intended_send
+    }
+
+    pub fn actual_send(&self) -> Duration {
+        self.actual_send
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn send_delay(&self) -> Duration {
+        self.actual_send.saturating_sub(self.intended_send)
+    }
+
+    /// Like the latency of a sample, counted from when the request should have gone out.
+    pub fn latency(&self) -> Duration {
+        self.send_delay() + self.elapsed
+    }
+
+    pub fn error(&self) -> &RequestError {
+        &self.error
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckCount {
+    passes: u64,
+    fails: u64,
+}
+
+impl CheckCount {
+    pub fn passes(&self) -> u64 {
+        self.passes
+    }
+
+    pub fn fails(&self) -> u64 {
+        self.fails
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserResult {
+    user: usize,
+    latencies: Vec<LatencyRecorder>,
+    phases: Vec<BTreeMap<Phase, LatencyRecorder>>,
+    stage_latencies: BTreeMap<(usize, usize), LatencyRecorder>,
+    status_classes: Vec<BTreeMap<StatusClass, u64>>,
+    checks: Vec<Vec<CheckCount>>,
+    retain_samples: bool,
+    samples: Vec<Sample>,
+    failures: Vec<Failure>,
+}
+
+impl UserResult {
+    pub fn new(user: usize, latencies: Vec<LatencyRecorder>, retain_samples: bool) -> Self {
+        Self {
+            user,
+            phases: vec![BTreeMap::new(); latencies.len()],
+            stage_latencies: BTreeMap::new(),
+            status_classes: vec![BTreeMap::new(); latencies.len()],
+            checks: vec![vec![]; latencies.len()],
+            latencies,
+            retain_samples,
+            samples: vec![],
+            failures: vec![],
+        }
+    }
+
+    pub fn record(&mut self, sample: Sample) {
+        let latencies = &mut self.latencies[sample.definition()];
+        for (phase, duration) in sample.response().timings().phases() {
+            self.phases[sample.definition()]
+                .entry(phase)
+                .or_insert_with(|| latencies.empty_like())
+                .record(duration);
+        }
+        if let Some(stage) = sample.stage() {
+            self.stage_latencies
+                .entry((stage, sample.definition()))
+                .or_insert_with(|| latencies.empty_like())
+                .record(sample.latency());
+        }
+        latencies.record(sample.latency());
+        if self.retain_samples {
+            self.samples.push(sample);
+        }
+    }
+
+    pub fn record_status(&mut self, definition: usize, status_class: StatusClass) {
+        *self.status_classes[definition]
+            .entry(status_class)
+            .or_default() += 1;
+    }
+
+    pub fn record_check(&mut self, definition: usize, check: usize, passed: bool) {
+        let checks = &mut self.checks[definition];
+        if checks.len() <= check {
+            checks.resize(check + 1, CheckCount::default());
+        }
+        if passed {
+            checks[check].passes += 1;
+        } else {
+            checks[check].fails += 1;
+        }
+    }
+
+    pub fn record_failure(&mut self, failure: Failure) {
+        self.failures.push(failure);
+    }
+
+    pub fn user(&self) -> usize {
+        self.user
+    }
+
+    pub fn latencies(&self) -> &[LatencyRecorder] {
+        &self.latencies
+    }
+
+    pub fn phases(&self) -> &[BTreeMap<Phase, LatencyRecorder>] {
+        &self.phases
+    }
+
+    /// Latencies of profile runs, by stage and definition.
+    pub fn stage_latencies(&self) -> &BTreeMap<(usize, usize), LatencyRecorder> {
+        &self.stage_latencies
+    }
+
+    pub fn status_classes(&self) -> &[BTreeMap<StatusClass, u64>] {
+        &self.status_classes
+    }
+
+    pub fn checks(&self) -> &[Vec<CheckCount>] {
+        &self.checks
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+}
+
+/// A sample of `definition` whose response took `latency_millis`.
+#[cfg(test)]
+pub(crate) fn sample_with_millis(definition: usize, latency_millis: u64) -> Sample {
+    Sample::new(
+        definition,
+        Duration::ZERO,
+        Duration::ZERO,
+        TimedResponse::new(String::from("ok"), Duration::from_millis(latency_millis)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::interface::ResponseTimings;
+
+    #[test]
+    fn latency_of_punctual_sample_is_response_time() {
+        let sample = Sample::new(
+            0,
+            Duration::from_millis(10),
+            Duration::from_millis(10),
+            TimedResponse::new(String::from("ok"), Duration::from_millis(5)),
+        );
+
+        assert_eq!(sample.send_delay(), Duration::ZERO);
+        assert_eq!(sample.latency(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn latency_includes_delayed_send() {
+        let sample = Sample::new(
+            0,
+            Duration::from_millis(10),
+            Duration::from_millis(40),
+            TimedResponse::new(String::from("ok"), Duration::from_millis(5)),
+        );
+
+        assert_eq!(sample.send_delay(), Duration::from_millis(30));
+        assert_eq!(sample.latency(), Duration::from_millis(35));
+    }
+
+    #[test]
+    fn failure_latency_includes_delayed_send() {
+        let failure = Failure::new(
+            0,
+            Duration::from_millis(10),
+            Duration::from_millis(40),
+            Duration::from_millis(5),
+            RequestError::Timeout,
+        );
+
+        assert_eq!(failure.send_delay(), Duration::from_millis(30));
+        assert_eq!(failure.latency(), Duration::from_millis(35));
+    }
+
+    #[test]
+    fn records_latency_per_definition() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+
+        result.record(sample_with_millis(1, 10));
+        result.record(sample_with_millis(1, 20));
+
+        assert!(result.latencies()[0].is_empty());
+        assert_eq!(result.latencies()[1].count(), 2);
+        assert!(result.samples().is_empty());
+    }
+
+    #[test]
+    fn records_phases_per_definition() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+        let response = TimedResponse::new(String::from("ok"), Duration::ZERO).with_timings(
+            ResponseTimings::new(Duration::from_millis(8), Duration::from_millis(2)),
+        );
+
+        result.record(Sample::new(1, Duration::ZERO, Duration::ZERO, response));
+
+        assert!(result.phases()[0].is_empty());
+        assert_eq!(
+            result.phases()[1]
+                .iter()
+                .map(|(phase, latencies)| (*phase, latencies.count()))
+                .collect::<Vec<_>>(),
+            vec![(Phase::Headers, 1), (Phase::Body, 1)]
+        );
+        assert_eq!(result.latencies()[1].count(), 1);
+    }
+
+    #[test]
+    fn records_latency_per_stage_and_definition() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+
+        result.record(sample_with_millis(0, 10));
+        result.record(sample_with_millis(1, 10).with_stage(Some(0)));
+        result.record(sample_with_millis(1, 20).with_stage(Some(2)));
+        result.record(sample_with_millis(1, 30).with_stage(Some(2)));
+
+        assert_eq!(
+            result
+                .stage_latencies()
+                .iter()
+                .map(|(key, latencies)| (*key, latencies.count()))
+                .collect::<Vec<_>>(),
+            vec![((0, 1), 1), ((2, 1), 2)]
+        );
+        assert_eq!(result.latencies()[1].count(), 3);
+    }
+
+    #[test]
+    fn retains_samples_on_request() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default()], true);
+
+        result.record(sample_with_millis(0, 10));
+
+        assert_eq!(result.latencies()[0].count(), 1);
+        assert_eq!(result.samples(), &[sample_with_millis(0, 10)]);
+    }
+
+    #[test]
+    fn failures_are_always_retained() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+        let failure = Failure::new(
+            1,
+            Duration::ZERO,
+            Duration::ZERO,
+            Duration::from_millis(3),
+            RequestError::RequestUnsuccesful,
+        );
+
+        result.record_failure(failure.clone());
+
+        assert_eq!(result.failures(), &[failure]);
+        assert!(result.latencies()[1].is_empty());
+    }
+
+    #[test]
+    fn counts_status_classes_per_definition() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+
+        result.record_status(1, StatusClass::Success);
+        result.record_status(1, StatusClass::ServerError);
+        result.record_status(1, StatusClass::Success);
+
+        assert!(result.status_classes()[0].is_empty());
+        assert_eq!(
+            result.status_classes()[1],
+            BTreeMap::from([(StatusClass::Success, 2), (StatusClass::ServerError, 1)])
+        );
+    }
+
+    #[test]
+    fn counts_checks_per_definition() {
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+
+        result.record_check(1, 1, true);
+        result.record_check(1, 1, false);
+        result.record_check(1, 1, true);
+
+        assert!(result.checks()[0].is_empty());
+        assert_eq!(
+            result.checks()[1]
+                .iter()
+                .map(|count| (count.passes(), count.fails()))
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (2, 1)]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::load_test::profile::LoadProfile;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunMode {
+    Iterations(usize),
+    Duration(Duration),
+    Profile(LoadProfile),
+}
+
+impl RunMode {
+    pub(crate) fn allows_iteration(&self, iteration: usize, elapsed: Duration) -> bool {
+        match self {
+            RunMode::Iterations(iterations) => iteration < *iterations,
+            RunMode::Duration(_) | RunMode::Profile(_) => self.allows_request(elapsed),
+        }
+    }
+
+    pub(crate) fn allows_request(&self, elapsed: Duration) -> bool {
+        match self {
+            RunMode::Iterations(_) => true,
+            RunMode::Duration(duration) => elapsed < *duration,
+            RunMode::Profile(profile) => elapsed < profile.total_duration(),
+        }
+    }
+
+    /// When requests stop being sent, if the run mode is bounded in time.
+    pub(crate) fn deadline(&self) -> Option<Duration> {
+        match self {
+            RunMode::Iterations(_) => None,
+            RunMode::Duration(duration) => Some(*duration),
+            RunMode::Profile(profile) => Some(profile.total_duration()),
+        }
+    }
+
+    pub(crate) fn user_active(&self, user: usize, elapsed: Duration) -> bool {
+        match self {
+            RunMode::Profile(profile) => user < profile.users_at(elapsed),
+            RunMode::Iterations(_) | RunMode::Duration(_) => true,
+        }
+    }
+
+    pub(crate) fn stage_at(&self, elapsed: Duration) -> Option<usize> {
+        match self {
+            RunMode::Profile(profile) => profile.stage_at(elapsed),
+            RunMode::Iterations(_) | RunMode::Duration(_) => None,
+        }
+    }
+}
+
+impl Default for RunMode {
+    fn default() -> Self {
+        RunMode::Iterations(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load_test::profile::Stage;
+
+    #[test]
+    fn iterations_stop_after_count() {
+        let run_mode = RunMode::Iterations(2);
+
+        assert!(run_mode.allows_iteration(0, Duration::from_secs(100)));
+        assert!(run_mode.allows_iteration(1, Duration::from_secs(100)));
+        assert!(!run_mode.allows_iteration(2, Duration::ZERO));
+    }
+
+    #[test]
+    fn iterations_never_interrupt_requests() {
+        assert!(RunMode::Iterations(1).allows_request(Duration::from_secs(1_000)));
+        assert_eq!(RunMode::Iterations(1).deadline(), None);
+    }
+
+    #[test]
+    fn duration_stops_after_deadline() {
+        let run_mode = RunMode::Duration(Duration::from_secs(1));
+
+        assert!(run_mode.allows_iteration(1_000, Duration::from_millis(999)));
+        assert!(run_mode.allows_request(Duration::from_millis(999)));
+        assert!(!run_mode.allows_iteration(0, Duration::from_secs(1)));
+        assert!(!run_mode.allows_request(Duration::from_secs(1)));
+        assert_eq!(run_mode.deadline(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn profile_stops_after_last_stage() {
+        let run_mode = RunMode::Profile(LoadProfile::new(
+            0,
+            vec![
+                Stage::new(Duration::from_secs(1), 2),
+                Stage::new(Duration::from_secs(1), 2),
+            ],
+        ));
+
+        assert!(run_mode.allows_request(Duration::from_millis(1_999)));
+        assert!(!run_mode.allows_request(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn profile_activates_users_by_stage() {
+        let run_mode = RunMode::Profile(LoadProfile::new(
+            0,
+            vec![Stage::new(Duration::from_secs(2), 2)],
+        ));
+
+        assert!(!run_mode.user_active(0, Duration::ZERO));
+        assert!(run_mode.user_active(0, Duration::from_secs(1)));
+        assert!(!run_mode.user_active(1, Duration::from_secs(1)));
+        assert_eq!(run_mode.stage_at(Duration::from_secs(1)), Some(0));
+    }
+
+    #[test]
+    fn fixed_modes_keep_all_users_active() {
+        assert!(RunMode::Iterations(1).user_active(10, Duration::ZERO));
+        assert_eq!(RunMode::Iterations(1).stage_at(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn default_runs_once() {
+        assert_eq!(RunMode::default(), RunMode::Iterations(1));
+    }
+}
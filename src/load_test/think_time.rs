@@ -0,0 +1,112 @@
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Normal};
+use std::time::Duration;
+
+/// How long a virtual user pauses, like a real user reading a page before the next click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThinkTime {
+    Constant(Duration),
+    Uniform {
+        min: Duration,
+        max: Duration,
+    },
+    /// Draws below zero are cut off at zero.
+    Normal {
+        mean: Duration,
+        std_dev: Duration,
+    },
+    Exponential {
+        mean: Duration,
+    },
+}
+
+impl ThinkTime {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        match *self {
+            ThinkTime::Constant(duration) => duration,
+            ThinkTime::Uniform { min, max } => rng.gen_range(min.min(max)..=min.max(max)),
+            ThinkTime::Normal { mean, std_dev } => {
+                let normal = Normal::new(mean.as_secs_f64(), std_dev.as_secs_f64())
+                    .expect("Durations are finite and not negative.");
+                Duration::from_secs_f64(normal.sample(rng).max(0.0))
+            }
+            ThinkTime::Exponential { mean } if mean.is_zero() => Duration::ZERO,
+            ThinkTime::Exponential { mean } => {
+                let exponential = Exp::new(mean.as_secs_f64().recip())
+                    .expect("Rate of a positive mean is valid.");
+                Duration::from_secs_f64(exponential.sample(rng))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn mean_of(think_time: ThinkTime, samples: u32) -> Duration {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..samples)
+            .map(|_| think_time.sample(&mut rng))
+            .sum::<Duration>()
+            / samples
+    }
+
+    #[test]
+    fn constant_think_time() {
+        let think_time = ThinkTime::Constant(Duration::from_millis(250));
+
+        assert_eq!(
+            think_time.sample(&mut StdRng::seed_from_u64(0)),
+            Duration::from_millis(250)
+        );
+    }
+
+    #[test]
+    fn uniform_stays_within_bounds() {
+        let think_time = ThinkTime::Uniform {
+            min: Duration::from_millis(100),
+            max: Duration::from_millis(200),
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert!((0..1_000).all(|_| {
+            let sample = think_time.sample(&mut rng);
+            sample >= Duration::from_millis(100) && sample <= Duration::from_millis(200)
+        }));
+    }
+
+    #[test]
+    fn normal_is_never_negative() {
+        let think_time = ThinkTime::Normal {
+            mean: Duration::from_millis(10),
+            std_dev: Duration::from_millis(100),
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+
+        assert!((0..1_000).any(|_| think_time.sample(&mut rng).is_zero()));
+    }
+
+    #[test]
+    fn random_think_times_center_on_their_mean() {
+        let normal = ThinkTime::Normal {
+            mean: Duration::from_secs(1),
+            std_dev: Duration::from_millis(100),
+        };
+        let exponential = ThinkTime::Exponential {
+            mean: Duration::from_secs(1),
+        };
+
+        for think_time in [normal, exponential] {
+            let mean = mean_of(think_time, 10_000);
+            assert!(
+                mean > Duration::from_millis(950) && mean < Duration::from_millis(1_050),
+                "{:?} averaged {:?}",
+                think_time,
+                mean
+            );
+        }
+    }
+}
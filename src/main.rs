@@ -1,38 +1,161 @@
-use loadtest::request::definition::RequestDefinition;
-use loadtest::request::reqwest_based::ReqwestConnection;
-use loadtest::tsp_specific::cities;
-use loadtest::LoadTest;
-
-static HOST: &str = "http://localhost/";
-
-fn main() {
-    let six_cities = cities::six();
-    let fivteen_cities = cities::fiveteen();
-    let twenty_nine_cities = cities::twenty_nine();
-
-    let client = ReqwestConnection::new(HOST);
-
-    let load_test = LoadTest::new(
-        &client,
-        vec![
-            RequestDefinition::GET { endpoint: "/alive" },
-            RequestDefinition::POST {
-                endpoint: "/tsp",
-                to_json: &six_cities,
-            },
-            RequestDefinition::POST {
-                endpoint: "/tsp",
-                to_json: &fivteen_cities,
-            },
-            RequestDefinition::POST {
-                endpoint: "/tsp",
-                to_json: &twenty_nine_cities,
-            },
-        ],
+mod cli;
+
+use clap::Parser;
+use cli::{
+    Cli, Command, OutputArgs, OutputFormat, ReportArgs, RunArgs, EXIT_INVALID_INPUT,
+    EXIT_OUTPUT_FAILED, EXIT_THRESHOLDS_FAILED,
+};
+use loadtest::load_test::executor::Executor;
+use loadtest::scenario::spec::Scenario;
+use loadtest::statistics::summary::Summary;
+use loadtest::statistics::threshold::evaluate_all;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn main() -> ExitCode {
+    let outcome = match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Validate { scenario } => validate(&scenario),
+        Command::Report(args) => report(args),
+    };
+    match outcome {
+        Ok(code) => code,
+        Err((code, message)) => {
+            eprintln!("{}", message);
+            ExitCode::from(code)
+        }
+    }
+}
+
+type Outcome = Result<ExitCode, (u8, String)>;
+
+fn run(args: RunArgs) -> Outcome {
+    let scenario = override_scenario(load_scenario(&args.scenario)?, &args)?;
+
+    let definitions = scenario.request_definitions();
+    let user_results = if args.run_async {
+        let runtime = tokio::runtime::Runtime::new().expect("Tokio runtime can start.");
+        runtime.block_on(
+            scenario
+                .load_test(Arc::new(scenario.async_connection()))
+                .run_async(),
+        )
+    } else {
+        scenario.load_test(Arc::new(scenario.connection())).run()
+    };
+    let summary = Summary::new(&definitions, &user_results)
+        .expect("Virtual users record latencies with the same bounds.");
+
+    write_summary(&summary, &args.output)?;
+    Ok(check_thresholds(&scenario, &summary))
+}
+
+fn override_scenario(mut scenario: Scenario, args: &RunArgs) -> Result<Scenario, (u8, String)> {
+    if let Some(host) = &args.host {
+        scenario = scenario.with_host(host);
+    }
+    if let Some(concurrency) = args.concurrency {
+        scenario = scenario.with_virtual_users(concurrency);
+    }
+    if let Some(iterations) = args.iterations {
+        scenario = scenario.with_iterations(iterations);
+    }
+    if let Some(duration) = args.duration {
+        let duration = Duration::try_from_secs_f64(duration).map_err(|error| {
+            (
+                EXIT_INVALID_INPUT,
+                format!("invalid duration {}: {}", duration, error),
+            )
+        })?;
+        scenario = scenario.with_duration(duration);
+    }
+    if let Some(timeout) = args.timeout {
+        let timeout = Duration::try_from_secs_f64(timeout).map_err(|error| {
+            (
+                EXIT_INVALID_INPUT,
+                format!("invalid timeout {}: {}", timeout, error),
+            )
+        })?;
+        scenario = scenario.with_timeout(timeout);
+    }
+    if let Some(seed) = args.seed {
+        scenario = scenario.with_seed(seed);
+    }
+    if let Some(rate) = args.rate {
+        let executor = Executor::constant_arrival_rate(rate)
+            .map_err(|error| (EXIT_INVALID_INPUT, error.to_string()))?;
+        scenario = scenario.with_executor(executor);
+    }
+    Ok(scenario)
+}
+
+fn validate(path: &Path) -> Outcome {
+    let scenario = load_scenario(path)?;
+    println!(
+        "{}: {} requests against {}",
+        path.display(),
+        scenario.request_definitions().len(),
+        scenario.host()
     );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn report(args: ReportArgs) -> Outcome {
+    let results = fs::read_to_string(&args.results).map_err(|error| {
+        (
+            EXIT_INVALID_INPUT,
+            format!("could not read '{}': {}", args.results.display(), error),
+        )
+    })?;
+    let summary: Summary = serde_json::from_str(&results).map_err(|error| {
+        (
+            EXIT_INVALID_INPUT,
+            format!("invalid results '{}': {}", args.results.display(), error),
+        )
+    })?;
 
-    let reponses = load_test.run();
-    for response in reponses {
-        println!("{:?}", response)
+    write_summary(&summary, &args.output)?;
+    match &args.scenario {
+        Some(scenario) => Ok(check_thresholds(&load_scenario(scenario)?, &summary)),
+        None => Ok(ExitCode::SUCCESS),
+    }
+}
+
+fn load_scenario(path: &Path) -> Result<Scenario, (u8, String)> {
+    Scenario::from_path(path).map_err(|error| (EXIT_INVALID_INPUT, error.to_string()))
+}
+
+fn write_summary(summary: &Summary, output: &OutputArgs) -> Result<(), (u8, String)> {
+    let rendered = match output.format {
+        OutputFormat::Text => summary.to_string(),
+        OutputFormat::Json => serde_json::to_string_pretty(summary)
+            .map_err(|error| (EXIT_OUTPUT_FAILED, error.to_string()))?,
+    };
+    match &output.output {
+        Some(path) => fs::write(path, rendered).map_err(|error| {
+            (
+                EXIT_OUTPUT_FAILED,
+                format!("could not write '{}': {}", path.display(), error),
+            )
+        }),
+        None => {
+            println!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+fn check_thresholds(scenario: &Scenario, summary: &Summary) -> ExitCode {
+    let violations = evaluate_all(scenario.thresholds(), summary);
+    if violations.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("Thresholds failed:");
+    for violation in violations {
+        eprintln!("{}", violation);
     }
+    ExitCode::from(EXIT_THRESHOLDS_FAILED)
 }
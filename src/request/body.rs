@@ -0,0 +1,334 @@
+use crate::request::variables::{has_placeholder, Variables};
+use bytes::Bytes;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Request bodies are encoded when they are created, so every request of a load test
+/// shares the same bytes instead of serializing again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Json(Bytes),
+    Text(Bytes),
+    Bytes { content_type: String, data: Bytes },
+    Form(Bytes),
+    Multipart(Vec<Part>),
+    MessagePack(Bytes),
+    Cbor(Bytes),
+}
+
+impl Body {
+    pub fn json(value: &(impl serde::Serialize + ?Sized)) -> Result<Self, BodyError> {
+        serde_json::to_vec(value)
+            .map(|data| Body::Json(data.into()))
+            .map_err(|error| BodyError::Serialization(error.to_string()))
+    }
+
+    pub fn message_pack(value: &(impl serde::Serialize + ?Sized)) -> Result<Self, BodyError> {
+        rmp_serde::to_vec_named(value)
+            .map(|data| Body::MessagePack(data.into()))
+            .map_err(|error| BodyError::Serialization(error.to_string()))
+    }
+
+    pub fn cbor(value: &(impl serde::Serialize + ?Sized)) -> Result<Self, BodyError> {
+        let mut data = vec![];
+        ciborium::ser::into_writer(value, &mut data)
+            .map_err(|error| BodyError::Serialization(error.to_string()))?;
+        Ok(Body::Cbor(data.into()))
+    }
+
+    /// JSON written as text, for placeholders that stand for whole values and only make
+    /// it valid once substituted.
+    pub fn json_template(template: &str) -> Self {
+        Body::Json(Bytes::copy_from_slice(template.as_bytes()))
+    }
+
+    pub fn text(text: &str) -> Self {
+        Body::Text(Bytes::copy_from_slice(text.as_bytes()))
+    }
+
+    pub fn bytes(content_type: &str, data: impl Into<Bytes>) -> Self {
+        Body::Bytes {
+            content_type: content_type.to_string(),
+            data: data.into(),
+        }
+    }
+
+    /// Fields are URL encoded right away.
+    pub fn form(fields: &[(&str, &str)]) -> Self {
+        Body::Form(encode_form(fields.iter().copied()))
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        match self {
+            Body::Json(_) => Some("application/json"),
+            Body::Text(_) => Some("text/plain; charset=utf-8"),
+            Body::Bytes { content_type, .. } => Some(content_type),
+            Body::Form(_) => Some("application/x-www-form-urlencoded"),
+            Body::Multipart(_) => None,
+            Body::MessagePack(_) => Some("application/msgpack"),
+            Body::Cbor(_) => Some("application/cbor"),
+        }
+    }
+
+    pub fn encoded(&self) -> Option<&Bytes> {
+        match self {
+            Body::Json(data)
+            | Body::Text(data)
+            | Body::Bytes { data, .. }
+            | Body::MessagePack(data)
+            | Body::Form(data)
+            | Body::Cbor(data) => Some(data),
+            Body::Multipart(_) => None,
+        }
+    }
+
+    /// Whether `substitute` can change this body.
+    pub(crate) fn has_placeholders(&self) -> bool {
+        match self {
+            Body::Json(data) | Body::Text(data) => data.windows(2).any(|pair| pair == b"{{"),
+            Body::Form(data) => {
+                form_urlencoded::parse(data).any(|(_, value)| has_placeholder(&value))
+            }
+            Body::Bytes { .. } | Body::Multipart(_) | Body::MessagePack(_) | Body::Cbor(_) => false,
+        }
+    }
+
+    /// Substitutes variables into JSON, text and form bodies. Binary bodies are sent as
+    /// they are.
+    pub fn substitute(&self, variables: &Variables) -> Body {
+        match self {
+            Body::Json(data) => Body::Json(substitute_utf8(data, |text| {
+                variables.substitute_json(text)
+            })),
+            Body::Text(data) => {
+                Body::Text(substitute_utf8(data, |text| variables.substitute(text)))
+            }
+            Body::Form(data) => Body::Form(encode_form(
+                form_urlencoded::parse(data)
+                    .map(|(name, value)| (name, variables.substitute(&value).into_owned())),
+            )),
+            Body::Bytes { .. } | Body::Multipart(_) | Body::MessagePack(_) | Body::Cbor(_) => {
+                self.clone()
+            }
+        }
+    }
+}
+
+fn encode_form<K: AsRef<str>, V: AsRef<str>>(fields: impl IntoIterator<Item = (K, V)>) -> Bytes {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(fields)
+        .finish()
+        .into()
+}
+
+fn substitute_utf8(data: &Bytes, substitute: impl Fn(&str) -> Cow<'_, str>) -> Bytes {
+    match std::str::from_utf8(data).map(substitute) {
+        Ok(Cow::Owned(substituted)) => Bytes::from(substituted),
+        Ok(Cow::Borrowed(_)) | Err(_) => data.clone(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyError {
+    Serialization(String),
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Serialization(message) => {
+                write!(f, "body serialization failed: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    name: String,
+    data: Bytes,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl Part {
+    pub fn text(name: &str, value: &str) -> Self {
+        Self::bytes(name, value.to_string())
+    }
+
+    pub fn bytes(name: &str, data: impl Into<Bytes>) -> Self {
+        Self {
+            name: name.to_string(),
+            data: data.into(),
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    pub fn with_file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(file_name.to_string());
+        self
+    }
+
+    pub fn with_content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn content_types() {
+        let body = serde_json::json!({"name": "Steven"});
+
+        assert_eq!(
+            [
+                Body::json(&body).unwrap(),
+                Body::text("hello"),
+                Body::bytes("image/png", vec![0x89, 0x50]),
+                Body::form(&[("name", "Steven")]),
+                Body::Multipart(vec![Part::text("name", "Steven")]),
+                Body::message_pack(&body).unwrap(),
+                Body::cbor(&body).unwrap(),
+            ]
+            .iter()
+            .map(Body::content_type)
+            .collect::<Vec<_>>(),
+            vec![
+                Some("application/json"),
+                Some("text/plain; charset=utf-8"),
+                Some("image/png"),
+                Some("application/x-www-form-urlencoded"),
+                None,
+                Some("application/msgpack"),
+                Some("application/cbor"),
+            ]
+        );
+    }
+
+    #[test]
+    fn bodies_are_encoded_once() {
+        let body = Body::json(&serde_json::json!({"name": "Steven"})).unwrap();
+        let shared = body.clone();
+
+        assert_eq!(
+            body.encoded().unwrap().as_ref(),
+            br#"{"name":"Steven"}"#.as_slice()
+        );
+        assert_eq!(
+            body.encoded().unwrap().as_ptr(),
+            shared.encoded().unwrap().as_ptr()
+        );
+    }
+
+    #[test]
+    fn forms_are_url_encoded_once() {
+        let form = Body::form(&[("name", "{{name}}"), ("city", "Zürich")]);
+
+        assert_eq!(
+            form.encoded().unwrap().as_ref(),
+            b"name=%7B%7Bname%7D%7D&city=Z%C3%BCrich".as_slice()
+        );
+        assert!(form.has_placeholders());
+        assert!(!Body::form(&[("city", "Zürich")]).has_placeholders());
+    }
+
+    #[test]
+    fn substitute_variables() {
+        let mut variables = Variables::default();
+        variables.set("name", String::from("Ste\"ven"));
+
+        assert_eq!(
+            Body::json(&serde_json::json!({"name": "{{name}}"}))
+                .unwrap()
+                .substitute(&variables),
+            Body::Json(Bytes::from_static(br#"{"name":"Ste\"ven"}"#))
+        );
+        assert_eq!(
+            Body::text("hello {{name}}").substitute(&variables),
+            Body::text("hello Ste\"ven")
+        );
+        assert_eq!(
+            Body::form(&[("name", "{{name}}")]).substitute(&variables),
+            Body::form(&[("name", "Ste\"ven")])
+        );
+        assert_eq!(
+            Body::json_template(r#"{"names": {{names}}}"#).substitute(&variables),
+            Body::Json(Bytes::from_static(br#"{"names": {{names}}}"#))
+        );
+        variables.set("names", String::from(r#"["Ste\"ven"]"#));
+        assert_eq!(
+            Body::json_template(r#"{"names": {{names}}}"#).substitute(&variables),
+            Body::Json(Bytes::from_static(br#"{"names": ["Ste\"ven"]}"#))
+        );
+        let binary = Body::bytes("application/octet-stream", "{{name}}");
+        assert_eq!(binary.substitute(&variables), binary);
+    }
+
+    #[test]
+    fn bodies_without_placeholders_stay_shared() {
+        let mut variables = Variables::default();
+        variables.set("name", String::from("Steven"));
+        let body = Body::json(&serde_json::json!({"name": "Steven"})).unwrap();
+
+        assert_eq!(
+            body.substitute(&variables).encoded().unwrap().as_ptr(),
+            body.encoded().unwrap().as_ptr()
+        );
+    }
+
+    struct Unserializable;
+
+    impl serde::Serialize for Unserializable {
+        fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            Err(serde::ser::Error::custom("not serializable"))
+        }
+    }
+
+    #[test]
+    fn serialization_errors() {
+        for body in [
+            Body::json(&Unserializable),
+            Body::message_pack(&Unserializable),
+            Body::cbor(&Unserializable),
+        ] {
+            assert!(matches!(body, Err(BodyError::Serialization(_))));
+        }
+    }
+
+    #[test]
+    fn file_part() {
+        let part = Part::bytes("upload", vec![1, 2, 3])
+            .with_file_name("cities.bin")
+            .with_content_type("application/octet-stream");
+
+        assert_eq!(part.name(), "upload");
+        assert_eq!(part.data().as_ref(), &[1, 2, 3]);
+        assert_eq!(part.file_name(), Some("cities.bin"));
+        assert_eq!(part.content_type(), Some("application/octet-stream"));
+    }
+}
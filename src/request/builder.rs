@@ -0,0 +1,147 @@
+use crate::request::body::{Body, BodyError, Part};
+use crate::request::definition::{Auth, ResolvedRequest};
+use crate::request::interface::RequestError;
+use bytes::Bytes;
+use std::time::Duration;
+
+/// The parts of the blocking and async reqwest builders a request definition needs.
+pub(crate) trait DefinitionBuilder: Sized {
+    fn header(self, name: &str, value: &str) -> Self;
+    fn query(self, query: &[(String, String)]) -> Self;
+    fn bearer_auth(self, token: &str) -> Self;
+    fn basic_auth(self, username: &str, password: Option<&String>) -> Self;
+    fn timeout(self, timeout: Duration) -> Self;
+    fn bytes(self, data: Bytes) -> Self;
+    fn multipart(self, parts: &[Part]) -> Result<Self, RequestError>;
+}
+
+macro_rules! impl_definition_builder {
+    ($builder:ty, $multipart:path, $shared_part:expr) => {
+        impl DefinitionBuilder for $builder {
+            fn header(self, name: &str, value: &str) -> Self {
+                self.header(name, value)
+            }
+
+            fn query(self, query: &[(String, String)]) -> Self {
+                self.query(query)
+            }
+
+            fn bearer_auth(self, token: &str) -> Self {
+                self.bearer_auth(token)
+            }
+
+            fn basic_auth(self, username: &str, password: Option<&String>) -> Self {
+                self.basic_auth(username, password)
+            }
+
+            fn timeout(self, timeout: Duration) -> Self {
+                self.timeout(timeout)
+            }
+
+            fn bytes(self, data: Bytes) -> Self {
+                self.body(data)
+            }
+
+            fn multipart(self, parts: &[Part]) -> Result<Self, RequestError> {
+                use $multipart as multipart;
+                let form = parts
+                    .iter()
+                    .try_fold(multipart::Form::new(), |form, part| {
+                        let mut multipart: multipart::Part = $shared_part(part.data().clone());
+                        if let Some(file_name) = part.file_name() {
+                            multipart = multipart.file_name(file_name.to_string());
+                        }
+                        if let Some(content_type) = part.content_type() {
+                            multipart = multipart
+                                .mime_str(content_type)
+                                .map_err(|error| BodyError::Serialization(error.to_string()))?;
+                        }
+                        Ok::<_, RequestError>(form.part(part.name().to_string(), multipart))
+                    })?;
+                Ok(self.multipart(form))
+            }
+        }
+    };
+}
+
+// Parts read the shared bytes of their body part instead of copying them.
+impl_definition_builder!(
+    reqwest::blocking::RequestBuilder,
+    reqwest::blocking::multipart,
+    |data: Bytes| {
+        let length = data.len() as u64;
+        reqwest::blocking::multipart::Part::reader_with_length(std::io::Cursor::new(data), length)
+    }
+);
+impl_definition_builder!(
+    reqwest::RequestBuilder,
+    reqwest::multipart,
+    |data: Bytes| reqwest::multipart::Part::stream(reqwest::Body::from(data))
+);
+
+pub(crate) fn url(host: &str, request: &ResolvedRequest<'_>) -> String {
+    format!("{}/{}", host, request.endpoint())
+}
+
+pub(crate) fn apply_definition<B: DefinitionBuilder>(
+    mut builder: B,
+    default_headers: &[(String, String)],
+    default_auth: Option<&Auth>,
+    request: &ResolvedRequest<'_>,
+) -> Result<B, RequestError> {
+    let overridden_defaults = default_headers.iter().filter(|(default_name, _)| {
+        !request
+            .headers()
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(default_name))
+    });
+    for (name, value) in overridden_defaults.chain(request.headers()) {
+        builder = builder.header(name, value);
+    }
+    if !request.query().is_empty() {
+        builder = builder.query(request.query());
+    }
+    builder = match request.auth().or(default_auth) {
+        Some(Auth::Bearer(token)) => builder.bearer_auth(token),
+        Some(Auth::Basic { username, password }) => builder.basic_auth(username, password.as_ref()),
+        None => builder,
+    };
+    if let Some(timeout) = request.timeout() {
+        builder = builder.timeout(timeout);
+    }
+    match request.body() {
+        Some(body) => with_body(builder, default_headers, request, body),
+        None => Ok(builder),
+    }
+}
+
+fn with_body<B: DefinitionBuilder>(
+    builder: B,
+    default_headers: &[(String, String)],
+    request: &ResolvedRequest<'_>,
+    body: &Body,
+) -> Result<B, RequestError> {
+    let builder = match body.content_type() {
+        Some(content_type) if !has_content_type(default_headers, request) => {
+            builder.header(http::header::CONTENT_TYPE.as_str(), content_type)
+        }
+        _ => builder,
+    };
+    match body {
+        Body::Multipart(parts) => builder.multipart(parts),
+        Body::Json(data)
+        | Body::Text(data)
+        | Body::Bytes { data, .. }
+        | Body::Form(data)
+        | Body::MessagePack(data)
+        | Body::Cbor(data) => Ok(builder.bytes(data.clone())),
+    }
+}
+
+/// Whether the request or the connection's defaults already set a Content-Type.
+fn has_content_type(default_headers: &[(String, String)], request: &ResolvedRequest<'_>) -> bool {
+    default_headers
+        .iter()
+        .chain(request.headers())
+        .any(|(name, _)| name.eq_ignore_ascii_case(http::header::CONTENT_TYPE.as_str()))
+}
@@ -0,0 +1,150 @@
+use crate::request::interface::{to_millisecond, TimedResponse};
+use http::StatusCode;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+type Predicate = Arc<dyn Fn(&TimedResponse) -> bool + Send + Sync>;
+
+#[derive(Clone)]
+pub enum Check {
+    Status(StatusCode),
+    BodyContains(String),
+    JsonPointerEquals {
+        pointer: String,
+        value: serde_json::Value,
+    },
+    MaxLatency(Duration),
+    Custom {
+        name: String,
+        predicate: Predicate,
+    },
+}
+
+impl Check {
+    pub fn custom(
+        name: &str,
+        predicate: impl Fn(&TimedResponse) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Check::Custom {
+            name: name.to_string(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    pub fn evaluate(&self, response: &TimedResponse) -> bool {
+        match self {
+            Check::Status(status) => response.status() == *status,
+            Check::BodyContains(expected) => response.text().contains(expected.as_str()),
+            Check::JsonPointerEquals { pointer, value } => {
+                serde_json::from_str::<serde_json::Value>(response.text())
+                    .ok()
+                    .and_then(|body| body.pointer(pointer).cloned())
+                    .is_some_and(|actual| actual == *value)
+            }
+            Check::MaxLatency(max_latency) => response.response_time() <= *max_latency,
+            Check::Custom { predicate, .. } => predicate(response),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Status(status) => write!(f, "status is {}", status.as_u16()),
+            Check::BodyContains(expected) => write!(f, "body contains '{}'", expected),
+            Check::JsonPointerEquals { pointer, value } => {
+                write!(f, "json '{}' equals {}", pointer, value)
+            }
+            Check::MaxLatency(max_latency) => {
+                write!(f, "latency <= {}ms", to_millisecond(*max_latency))
+            }
+            Check::Custom { name, .. } => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Debug for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Check({})", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::interface::text_response;
+    use serde_json::json;
+
+    #[test]
+    fn status_check() {
+        assert!(Check::Status(StatusCode::OK).evaluate(&text_response("")));
+        assert!(!Check::Status(StatusCode::OK)
+            .evaluate(&text_response("").with_status(StatusCode::NOT_FOUND)));
+    }
+
+    #[test]
+    fn body_contains_check() {
+        let check = Check::BodyContains(String::from("alive"));
+
+        assert!(check.evaluate(&text_response("I am alive!")));
+        assert!(!check.evaluate(&text_response("I am dead!")));
+    }
+
+    #[test]
+    fn json_pointer_check() {
+        let check = Check::JsonPointerEquals {
+            pointer: String::from("/solution/length"),
+            value: json!(42),
+        };
+
+        assert!(check.evaluate(&text_response(r#"{"solution": {"length": 42}}"#)));
+        assert!(!check.evaluate(&text_response(r#"{"solution": {"length": 43}}"#)));
+        assert!(!check.evaluate(&text_response(r#"{"solution": {}}"#)));
+        assert!(!check.evaluate(&text_response("not json")));
+    }
+
+    #[test]
+    fn max_latency_check() {
+        assert!(Check::MaxLatency(Duration::from_millis(20)).evaluate(&text_response("")));
+        assert!(!Check::MaxLatency(Duration::from_millis(19)).evaluate(&text_response("")));
+    }
+
+    #[test]
+    fn custom_check() {
+        let check = Check::custom("short body", |response| response.body_size() < 3);
+
+        assert!(check.evaluate(&text_response("ok")));
+        assert!(!check.evaluate(&text_response("too long")));
+    }
+
+    #[test]
+    fn display_checks() {
+        assert_eq!(
+            format!("{}", Check::Status(StatusCode::CREATED)),
+            "status is 201"
+        );
+        assert_eq!(
+            format!("{}", Check::BodyContains(String::from("alive"))),
+            "body contains 'alive'"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Check::JsonPointerEquals {
+                    pointer: String::from("/name"),
+                    value: json!("tsp"),
+                }
+            ),
+            "json '/name' equals \"tsp\""
+        );
+        assert_eq!(
+            format!("{}", Check::MaxLatency(Duration::from_micros(1_500))),
+            "latency <= 1.5ms"
+        );
+        assert_eq!(
+            format!("{}", Check::custom("tour is valid", |_| true)),
+            "tour is valid"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::request::dns::Resolver;
+use serde::{Deserialize, Deserializer};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpVersion {
+    /// HTTP/1.1, upgrading to HTTP/2 where the server negotiates it over TLS.
+    #[default]
+    Auto,
+    Http1,
+    /// HTTP/2 without negotiation, also over plain TCP.
+    Http2PriorKnowledge,
+}
+
+/// How a connection opens, reuses and times out the connections behind its requests.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConnectionOptions {
+    keep_alive: bool,
+    max_idle_per_host: Option<usize>,
+    per_user: bool,
+    http_version: HttpVersion,
+    #[serde(
+        rename = "connect_timeout_ms",
+        deserialize_with = "optional_milliseconds"
+    )]
+    connect_timeout: Option<Duration>,
+    #[serde(rename = "timeout_ms", deserialize_with = "optional_milliseconds")]
+    timeout: Option<Duration>,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            keep_alive: true,
+            max_idle_per_host: None,
+            per_user: false,
+            http_version: HttpVersion::default(),
+            connect_timeout: None,
+            timeout: None,
+        }
+    }
+}
+
+impl ConnectionOptions {
+    /// Without keep-alive every request opens a new connection.
+    pub fn with_keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    pub fn with_max_idle_per_host(mut self, max_idle_per_host: usize) -> Self {
+        self.max_idle_per_host = Some(max_idle_per_host);
+        self
+    }
+
+    /// Gives every virtual user its own client and connection pool.
+    pub fn with_per_user(mut self, per_user: bool) -> Self {
+        self.per_user = per_user;
+        self
+    }
+
+    pub fn with_http_version(mut self, http_version: HttpVersion) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    /// Applies to every request, since request definitions can only set a total timeout.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Limits every request from connecting until its response body is read. Request
+    /// definitions can override it with their own timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+
+    pub fn max_idle_per_host(&self) -> Option<usize> {
+        self.max_idle_per_host
+    }
+
+    pub fn per_user(&self) -> bool {
+        self.per_user
+    }
+
+    pub fn http_version(&self) -> HttpVersion {
+        self.http_version
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn idle_connections(&self) -> Option<usize> {
+        if self.keep_alive {
+            self.max_idle_per_host
+        } else {
+            Some(0)
+        }
+    }
+}
+
+fn optional_milliseconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    Duration::try_from_secs_f64(f64::deserialize(deserializer)? / 1_000.0)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+macro_rules! build_client {
+    ($name:ident, $builder:ty, $client:ty) => {
+        pub(crate) fn $name(options: &ConnectionOptions) -> $client {
+            let mut builder =
+                <$builder>::from(reqwest::ClientBuilder::new().dns_resolver(Arc::new(Resolver)));
+            if let Some(idle_connections) = options.idle_connections() {
+                builder = builder.pool_max_idle_per_host(idle_connections);
+            }
+            if let Some(connect_timeout) = options.connect_timeout {
+                builder = builder.connect_timeout(connect_timeout);
+            }
+            if let Some(timeout) = options.timeout {
+                builder = builder.timeout(timeout);
+            }
+            builder = match options.http_version {
+                HttpVersion::Auto => builder,
+                HttpVersion::Http1 => builder.http1_only(),
+                HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+            };
+            builder
+                .build()
+                .expect("Client builds without custom TLS configuration.")
+        }
+    };
+}
+
+build_client!(
+    blocking_client,
+    reqwest::blocking::ClientBuilder,
+    reqwest::blocking::Client
+);
+build_client!(async_client, reqwest::ClientBuilder, reqwest::Client);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keep_alive_by_default() {
+        let options = ConnectionOptions::default();
+
+        assert!(options.keep_alive());
+        assert!(!options.per_user());
+        assert_eq!(options.idle_connections(), None);
+        assert_eq!(options.http_version(), HttpVersion::Auto);
+        assert_eq!(options.timeout(), None);
+    }
+
+    #[test]
+    fn no_idle_connections_without_keep_alive() {
+        let options = ConnectionOptions::default()
+            .with_max_idle_per_host(8)
+            .with_keep_alive(false);
+
+        assert_eq!(options.idle_connections(), Some(0));
+        assert_eq!(options.with_keep_alive(true).idle_connections(), Some(8));
+    }
+
+    #[test]
+    fn deserialize_partial_options() {
+        let options: ConnectionOptions =
+            serde_json::from_str(r#"{"per_user": true, "http_version": "http2_prior_knowledge"}"#)
+                .unwrap();
+
+        assert_eq!(
+            options,
+            ConnectionOptions::default()
+                .with_per_user(true)
+                .with_http_version(HttpVersion::Http2PriorKnowledge)
+        );
+    }
+
+    #[test]
+    fn deserialize_timeouts_in_milliseconds() {
+        let options: ConnectionOptions =
+            serde_json::from_str(r#"{"connect_timeout_ms": 250, "timeout_ms": 1500.5}"#).unwrap();
+
+        assert_eq!(options.connect_timeout(), Some(Duration::from_millis(250)));
+        assert_eq!(options.timeout(), Some(Duration::from_micros(1_500_500)));
+        assert!(serde_json::from_str::<ConnectionOptions>(r#"{"timeout_ms": -1}"#).is_err());
+    }
+}
@@ -1,11 +1,457 @@
-use erased_serde::Serialize;
+use crate::load_test::think_time::ThinkTime;
+use crate::request::body::Body;
+use crate::request::check::Check;
+use crate::request::extract::Extractor;
+use crate::request::interface::TimedResponse;
+use crate::request::variables::{has_placeholder, Variables};
+use http::Method;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::time::Duration;
 
-pub enum RequestDefinition<'a> {
-    POST {
-        endpoint: &'a str,
-        to_json: &'a dyn Serialize,
-    },
-    GET {
-        endpoint: &'a str,
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    Bearer(String),
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: Option<String>,
     },
 }
+
+/// Which parts of a definition hold `{{name}}` placeholders, so resolving can skip the
+/// others.
+#[derive(Clone, Copy, Default)]
+struct Placeholders {
+    endpoint: bool,
+    headers: bool,
+    query: bool,
+    body: bool,
+}
+
+#[derive(Clone)]
+pub struct RequestDefinition {
+    method: Method,
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    auth: Option<Auth>,
+    body: Option<Body>,
+    checks: Vec<Check>,
+    timeout: Option<Duration>,
+    think_time: Option<ThinkTime>,
+    weight: u32,
+    extractions: Vec<(String, Extractor)>,
+    placeholders: Placeholders,
+}
+
+impl RequestDefinition {
+    pub fn new(method: Method, endpoint: &str) -> Self {
+        Self {
+            method,
+            endpoint: endpoint.to_string(),
+            headers: vec![],
+            query: vec![],
+            auth: None,
+            body: None,
+            checks: vec![],
+            timeout: None,
+            think_time: None,
+            weight: 1,
+            extractions: vec![],
+            placeholders: Placeholders {
+                endpoint: has_placeholder(endpoint),
+                ..Placeholders::default()
+            },
+        }
+    }
+
+    pub fn get(endpoint: &str) -> Self {
+        Self::new(Method::GET, endpoint)
+    }
+
+    pub fn post(endpoint: &str, body: Body) -> Self {
+        Self::new(Method::POST, endpoint).with_body(body)
+    }
+
+    pub fn put(endpoint: &str, body: Body) -> Self {
+        Self::new(Method::PUT, endpoint).with_body(body)
+    }
+
+    pub fn patch(endpoint: &str, body: Body) -> Self {
+        Self::new(Method::PATCH, endpoint).with_body(body)
+    }
+
+    pub fn delete(endpoint: &str) -> Self {
+        Self::new(Method::DELETE, endpoint)
+    }
+
+    pub fn head(endpoint: &str) -> Self {
+        Self::new(Method::HEAD, endpoint)
+    }
+
+    pub fn options(endpoint: &str) -> Self {
+        Self::new(Method::OPTIONS, endpoint)
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.placeholders.headers |= has_placeholder(value);
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_query(mut self, key: &str, value: &str) -> Self {
+        self.placeholders.query |= has_placeholder(value);
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn with_body(mut self, body: Body) -> Self {
+        self.placeholders.body = body.has_placeholders();
+        self.body = Some(body);
+        self
+    }
+
+    pub fn with_checks(mut self, checks: Vec<Check>) -> Self {
+        self.checks = checks;
+        self
+    }
+
+    /// Overrides the connection's total timeout for this request. Connect timeouts stay
+    /// with the connection and there is no read timeout, as reqwest 0.11 only takes a
+    /// total timeout per request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the load test's think time after this request.
+    pub fn with_think_time(mut self, think_time: ThinkTime) -> Self {
+        self.think_time = Some(think_time);
+        self
+    }
+
+    /// How often a weighted request mix picks this request relative to the others.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Stores what `extractor` finds in the response in the user's variable `name`.
+    pub fn with_extraction(mut self, name: &str, extractor: Extractor) -> Self {
+        self.extractions.push((name.to_string(), extractor));
+        self
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn query(&self) -> &[(String, String)] {
+        &self.query
+    }
+
+    pub fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+
+    pub fn body(&self) -> Option<&Body> {
+        self.body.as_ref()
+    }
+
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn think_time(&self) -> Option<ThinkTime> {
+        self.think_time
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn extractions(&self) -> &[(String, Extractor)] {
+        &self.extractions
+    }
+
+    /// This request with `{{name}}` placeholders in its endpoint, query, headers and body
+    /// replaced by the user's variables. Only parts with placeholders are copied.
+    pub fn resolve(&self, variables: &Variables) -> ResolvedRequest<'_> {
+        let mut resolved = ResolvedRequest::from(self);
+        if variables.is_empty() {
+            return resolved;
+        }
+        if self.placeholders.endpoint {
+            if let Cow::Owned(endpoint) = variables.substitute(&self.endpoint) {
+                resolved.endpoint = Some(endpoint);
+            }
+        }
+        if self.placeholders.headers {
+            resolved.headers = Some(substitute_pairs(&self.headers, variables));
+        }
+        if self.placeholders.query {
+            resolved.query = Some(substitute_pairs(&self.query, variables));
+        }
+        if self.placeholders.body {
+            resolved.body = self.body.as_ref().map(|body| body.substitute(variables));
+        }
+        resolved
+    }
+
+    pub fn extract(&self, response: &TimedResponse, variables: &mut Variables) {
+        for (name, extractor) in &self.extractions {
+            if let Some(value) = extractor.extract(response) {
+                variables.set(name, value);
+            }
+        }
+    }
+}
+
+fn substitute_pairs(pairs: &[(String, String)], variables: &Variables) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.clone(), variables.substitute(value).into_owned()))
+        .collect()
+}
+
+/// A request definition with a user's variables substituted. Parts without placeholders
+/// are read from the definition instead of being copied.
+#[derive(Clone)]
+pub struct ResolvedRequest<'a> {
+    definition: Cow<'a, RequestDefinition>,
+    endpoint: Option<String>,
+    headers: Option<Vec<(String, String)>>,
+    query: Option<Vec<(String, String)>>,
+    body: Option<Body>,
+}
+
+impl<'a> From<&'a RequestDefinition> for ResolvedRequest<'a> {
+    fn from(definition: &'a RequestDefinition) -> Self {
+        Self {
+            definition: Cow::Borrowed(definition),
+            endpoint: None,
+            headers: None,
+            query: None,
+            body: None,
+        }
+    }
+}
+
+impl From<RequestDefinition> for ResolvedRequest<'static> {
+    fn from(definition: RequestDefinition) -> Self {
+        Self {
+            definition: Cow::Owned(definition),
+            endpoint: None,
+            headers: None,
+            query: None,
+            body: None,
+        }
+    }
+}
+
+impl ResolvedRequest<'_> {
+    pub fn definition(&self) -> &RequestDefinition {
+        &self.definition
+    }
+
+    pub fn method(&self) -> &Method {
+        self.definition.method()
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.endpoint
+            .as_deref()
+            .unwrap_or_else(|| self.definition.endpoint())
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        self.headers
+            .as_deref()
+            .unwrap_or_else(|| self.definition.headers())
+    }
+
+    pub fn query(&self) -> &[(String, String)] {
+        self.query
+            .as_deref()
+            .unwrap_or_else(|| self.definition.query())
+    }
+
+    pub fn auth(&self) -> Option<&Auth> {
+        self.definition.auth()
+    }
+
+    pub fn body(&self) -> Option<&Body> {
+        self.body.as_ref().or_else(|| self.definition.body())
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.definition.timeout()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn methods_without_body() {
+        let definitions = [
+            RequestDefinition::get("/alive"),
+            RequestDefinition::delete("/tsp/jobs/1"),
+            RequestDefinition::head("/alive"),
+            RequestDefinition::options("/tsp"),
+        ];
+
+        assert_eq!(
+            definitions
+                .iter()
+                .map(|definition| (definition.method().clone(), definition.body().is_none()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Method::GET, true),
+                (Method::DELETE, true),
+                (Method::HEAD, true),
+                (Method::OPTIONS, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn methods_with_body() {
+        let body = serde_json::json!({"name": "Steven"});
+        let definitions = [
+            RequestDefinition::post("/users", Body::json(&body).unwrap()),
+            RequestDefinition::put("/users/1", Body::json(&body).unwrap()),
+            RequestDefinition::patch("/users/1", Body::json(&body).unwrap()),
+            RequestDefinition::delete("/users/1").with_body(Body::json(&body).unwrap()),
+        ];
+
+        assert_eq!(
+            definitions
+                .iter()
+                .map(|definition| (definition.method().clone(), definition.body().is_some()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Method::POST, true),
+                (Method::PUT, true),
+                (Method::PATCH, true),
+                (Method::DELETE, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn non_json_body() {
+        let definition =
+            RequestDefinition::post("/upload", Body::bytes("image/png", vec![0x89, 0x50]));
+
+        assert!(matches!(
+            definition.body(),
+            Some(Body::Bytes { content_type, data })
+                if content_type == "image/png" && data.as_ref() == [0x89, 0x50]
+        ));
+    }
+
+    #[test]
+    fn definitions_are_owned() {
+        fn assert_send_sync_static<T: Send + Sync + 'static>(_: &T) {}
+
+        let endpoint = String::from("/tsp");
+        let definition = RequestDefinition::post(
+            &endpoint,
+            Body::json(&serde_json::json!({"cities": 6})).unwrap(),
+        );
+        drop(endpoint);
+
+        assert_send_sync_static(&definition);
+        assert_eq!(definition.endpoint(), "/tsp");
+    }
+
+    #[test]
+    fn arbitrary_method() {
+        let definition = RequestDefinition::new(Method::from_bytes(b"PURGE").unwrap(), "/cache");
+
+        assert_eq!(definition.method().as_str(), "PURGE");
+        assert_eq!(definition.endpoint(), "/cache");
+    }
+
+    #[test]
+    fn headers_query_and_auth() {
+        let definition = RequestDefinition::get("/tsp/jobs")
+            .with_header("x-request-id", "42")
+            .with_query("status", "done")
+            .with_query("limit", "10")
+            .with_auth(Auth::Bearer(String::from("token")));
+
+        assert_eq!(
+            definition.headers(),
+            &[(String::from("x-request-id"), String::from("42"))]
+        );
+        assert_eq!(
+            definition.query(),
+            &[
+                (String::from("status"), String::from("done")),
+                (String::from("limit"), String::from("10"))
+            ]
+        );
+        assert_eq!(
+            definition.auth(),
+            Some(&Auth::Bearer(String::from("token")))
+        );
+    }
+
+    #[test]
+    fn resolve_placeholders() {
+        let definition = RequestDefinition::post("/tsp/jobs/{{job}}", Body::text("job {{job}}"))
+            .with_header("x-job", "{{job}}")
+            .with_query("job", "{{job}}");
+        let mut variables = Variables::default();
+        let resolved = definition.resolve(&variables);
+
+        assert!(matches!(resolved.definition, Cow::Borrowed(_)));
+        assert!(resolved.endpoint.is_none() && resolved.body.is_none());
+
+        variables.set("job", String::from("42"));
+        let resolved = definition.resolve(&variables);
+
+        assert_eq!(resolved.endpoint(), "/tsp/jobs/42");
+        assert_eq!(resolved.headers()[0].1, "42");
+        assert_eq!(resolved.query()[0].1, "42");
+        assert_eq!(resolved.body(), Some(&Body::text("job 42")));
+    }
+
+    #[test]
+    fn resolve_only_templated_parts() {
+        let definition = RequestDefinition::post("/tsp/jobs/{{job}}", Body::text("jobs"))
+            .with_header("accept", "text/plain")
+            .with_query("page", "1");
+        let mut variables = Variables::default();
+        variables.set("job", String::from("42"));
+        let resolved = definition.resolve(&variables);
+
+        assert_eq!(resolved.endpoint(), "/tsp/jobs/42");
+        assert!(resolved.headers.is_none());
+        assert!(resolved.query.is_none());
+        assert!(resolved.body.is_none());
+        assert_eq!(resolved.body(), Some(&Body::text("jobs")));
+    }
+}
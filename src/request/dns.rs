@@ -0,0 +1,35 @@
+use hyper::client::connect::dns::{GaiResolver, Name};
+use hyper::service::Service;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use std::fmt;
+use std::io;
+
+/// Resolves host names with `getaddrinfo` like reqwest does by default, but fails with
+/// a `ResolveError` so a failed lookup can be told apart from a failed connect.
+#[derive(Debug, Default)]
+pub(crate) struct Resolver;
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let mut resolver = GaiResolver::new();
+        Box::pin(async move {
+            let addrs = resolver.call(name).await.map_err(ResolveError)?;
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ResolveError(io::Error);
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not resolve host: {}", self.0)
+    }
+}
+
+impl std::error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::request::interface::TimedResponse;
+use regex::Regex;
+use std::fmt;
+
+/// Pulls a value out of a response to store in a per-user variable.
+#[derive(Debug, Clone)]
+pub enum Extractor {
+    /// String values are taken as they are, other JSON values in their JSON form.
+    JsonPointer(String),
+    /// The first capture group, or the whole match without groups.
+    Regex(Regex),
+    Header(String),
+}
+
+impl Extractor {
+    pub fn extract(&self, response: &TimedResponse) -> Option<String> {
+        match self {
+            Extractor::JsonPointer(pointer) => {
+                let body = serde_json::from_str::<serde_json::Value>(response.text()).ok()?;
+                match body.pointer(pointer)? {
+                    serde_json::Value::String(value) => Some(value.clone()),
+                    value => Some(value.to_string()),
+                }
+            }
+            Extractor::Regex(regex) => {
+                let captures = regex.captures(response.text())?;
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|value| value.as_str().to_string())
+            }
+            Extractor::Header(name) => response
+                .headers()
+                .get(name)?
+                .to_str()
+                .ok()
+                .map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extractor::JsonPointer(pointer) => write!(f, "json '{}'", pointer),
+            Extractor::Regex(regex) => write!(f, "regex '{}'", regex),
+            Extractor::Header(name) => write!(f, "header '{}'", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::interface::text_response;
+    use http::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn json_pointer() {
+        let response = text_response(r#"{"job": {"id": "a1b2", "cities": 6}}"#);
+
+        assert_eq!(
+            Extractor::JsonPointer(String::from("/job/id")).extract(&response),
+            Some(String::from("a1b2"))
+        );
+        assert_eq!(
+            Extractor::JsonPointer(String::from("/job/cities")).extract(&response),
+            Some(String::from("6"))
+        );
+        assert_eq!(
+            Extractor::JsonPointer(String::from("/job/tour")).extract(&response),
+            None
+        );
+        assert_eq!(
+            Extractor::JsonPointer(String::from("/job")).extract(&text_response("not json")),
+            None
+        );
+    }
+
+    #[test]
+    fn regex_prefers_first_group() {
+        let response = text_response("job 42 queued");
+
+        assert_eq!(
+            Extractor::Regex(Regex::new(r"job (\d+)").unwrap()).extract(&response),
+            Some(String::from("42"))
+        );
+        assert_eq!(
+            Extractor::Regex(Regex::new(r"\d+").unwrap()).extract(&response),
+            Some(String::from("42"))
+        );
+        assert_eq!(
+            Extractor::Regex(Regex::new("solved").unwrap()).extract(&response),
+            None
+        );
+    }
+
+    #[test]
+    fn header() {
+        let mut headers = HeaderMap::new();
+        headers.insert("location", HeaderValue::from_static("/tsp/jobs/42"));
+        let response = text_response("").with_headers(headers);
+
+        assert_eq!(
+            Extractor::Header(String::from("Location")).extract(&response),
+            Some(String::from("/tsp/jobs/42"))
+        );
+        assert_eq!(
+            Extractor::Header(String::from("etag")).extract(&response),
+            None
+        );
+    }
+}
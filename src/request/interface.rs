@@ -1,37 +1,266 @@
+use crate::request::body::BodyError;
+use crate::request::definition::ResolvedRequest;
+use crate::request::dns::ResolveError;
 use core::fmt;
-use erased_serde::Serialize;
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::future::Future;
 use std::time::Duration;
 pub trait HTTPClient {
-    fn get(&self, endpoint: &'_ str) -> Result<TimedResponse, RequestError>;
-    fn post<'a>(
+    fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError>;
+
+    /// A client of its own for the virtual user, or `None` to share this one.
+    fn for_user(&self, _user: usize) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+pub trait AsyncHTTPClient {
+    fn send(
         &self,
-        endpoint: &'a str,
-        body: &'a dyn Serialize,
-    ) -> Result<TimedResponse, RequestError>;
+        request: &ResolvedRequest<'_>,
+    ) -> impl Future<Output = Result<TimedResponse, RequestError>> + Send;
+
+    /// A client of its own for the virtual user, or `None` to share this one.
+    fn for_user(&self, _user: usize) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RequestError {
     RequestUnsuccesful,
+    Connect,
+    Dns,
+    Timeout,
+    Tls,
+    BodySerialization,
+    BodyRead,
+    Status(u16),
 }
 
 impl From<reqwest::Error> for RequestError {
-    fn from(_: reqwest::Error) -> Self {
-        RequestError::RequestUnsuccesful
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            RequestError::Timeout
+        } else if let Some(status) = error.status() {
+            RequestError::Status(status.as_u16())
+        } else if error.is_connect() {
+            classify_connect_error(&error)
+        } else if error.is_body() || error.is_decode() {
+            RequestError::BodyRead
+        } else {
+            RequestError::RequestUnsuccesful
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::RequestUnsuccesful => write!(f, "unsuccessful"),
+            RequestError::Connect => write!(f, "connect"),
+            RequestError::Dns => write!(f, "dns"),
+            RequestError::Timeout => write!(f, "timeout"),
+            RequestError::Tls => write!(f, "tls"),
+            RequestError::BodySerialization => write!(f, "body serialization"),
+            RequestError::BodyRead => write!(f, "body read"),
+            RequestError::Status(status) => write!(f, "status {}", status),
+        }
+    }
+}
+
+impl From<BodyError> for RequestError {
+    fn from(_: BodyError) -> Self {
+        RequestError::BodySerialization
+    }
+}
+
+fn classify_connect_error(error: &reqwest::Error) -> RequestError {
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if cause.is::<ResolveError>() {
+            return RequestError::Dns;
+        }
+        if cause.is::<native_tls::Error>() {
+            return RequestError::Tls;
+        }
+        source = cause.source();
+    }
+    RequestError::Connect
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StatusClass {
+    Informational,
+    Success,
+    Redirection,
+    ClientError,
+    ServerError,
+}
+
+impl StatusClass {
+    /// Only 4xx and 5xx responses are failures. Clients follow redirects, so a 3xx that
+    /// still arrives is a final answer such as 304 Not Modified.
+    pub fn is_error(&self) -> bool {
+        matches!(self, StatusClass::ClientError | StatusClass::ServerError)
+    }
+}
+
+impl From<StatusCode> for StatusClass {
+    fn from(status: StatusCode) -> Self {
+        if status.is_informational() {
+            StatusClass::Informational
+        } else if status.is_success() {
+            StatusClass::Success
+        } else if status.is_redirection() {
+            StatusClass::Redirection
+        } else if status.is_client_error() {
+            StatusClass::ClientError
+        } else {
+            StatusClass::ServerError
+        }
+    }
+}
+
+impl fmt::Display for StatusClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusClass::Informational => write!(f, "1xx"),
+            StatusClass::Success => write!(f, "2xx"),
+            StatusClass::Redirection => write!(f, "3xx"),
+            StatusClass::ClientError => write!(f, "4xx"),
+            StatusClass::ServerError => write!(f, "5xx"),
+        }
+    }
+}
+
+/// The parts of a response time that are measured separately.
+///
+/// DNS lookup, TCP connect and TLS handshake are not phases of their own. reqwest opens
+/// connections inside its pool, on its own runtime for blocking clients, and never says
+/// which request a new connection was opened for, so their time stays in `Headers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// From sending the request until the response headers arrive, including opening a
+    /// connection where none could be reused.
+    Headers,
+    /// Reading the response body.
+    Body,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Headers => write!(f, "headers"),
+            Phase::Body => write!(f, "body"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseTimings {
+    headers: Duration,
+    body: Duration,
+}
+
+impl ResponseTimings {
+    pub fn new(headers: Duration, body: Duration) -> Self {
+        Self { headers, body }
+    }
+
+    pub fn headers(&self) -> Duration {
+        self.headers
+    }
+
+    pub fn body(&self) -> Duration {
+        self.body
+    }
+
+    pub fn total(&self) -> Duration {
+        self.headers + self.body
+    }
+
+    pub fn phases(&self) -> impl Iterator<Item = (Phase, Duration)> {
+        [(Phase::Headers, self.headers), (Phase::Body, self.body)].into_iter()
     }
 }
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TimedResponse {
     text: String,
-    response_time: Duration,
+    timings: ResponseTimings,
+    status: StatusCode,
+    headers: HeaderMap,
+    body_size: usize,
 }
 impl TimedResponse {
     pub fn new(text: String, response_time: Duration) -> Self {
         Self {
+            body_size: text.len(),
             text,
-            response_time,
+            timings: ResponseTimings::new(response_time, Duration::ZERO),
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
         }
     }
+
+    /// Replaces the response time given to `new` with its phases.
+    pub fn with_timings(mut self, timings: ResponseTimings) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_body_size(mut self, body_size: usize) -> Self {
+        self.body_size = body_size;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn response_time(&self) -> Duration {
+        self.timings.total()
+    }
+
+    pub fn timings(&self) -> &ResponseTimings {
+        &self.timings
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn status_class(&self) -> StatusClass {
+        StatusClass::from(self.status)
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn body_size(&self) -> usize {
+        self.body_size
+    }
 }
 
 impl fmt::Display for TimedResponse {
@@ -40,7 +269,7 @@ impl fmt::Display for TimedResponse {
             f,
             "Reponse: '{}'\n Response_time: '{}'",
             self.text,
-            to_millisecond(self.response_time)
+            to_millisecond(self.response_time())
         )
     }
 }
@@ -49,6 +278,12 @@ pub(crate) fn to_millisecond(duration: Duration) -> f64 {
     duration.as_nanos() as f64 / 1_000_000.0
 }
 
+/// A 200 response with body `text` that took 20ms.
+#[cfg(test)]
+pub(crate) fn text_response(text: &str) -> TimedResponse {
+    TimedResponse::new(text.to_string(), Duration::from_millis(20))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -74,6 +309,70 @@ mod test {
         assert_eq!(to_millisecond(duration), 1.5)
     }
 
+    #[test]
+    fn display_request_errors() {
+        assert_eq!(format!("{}", RequestError::Dns), String::from("dns"));
+        assert_eq!(
+            format!("{}", RequestError::Status(503)),
+            String::from("status 503")
+        );
+    }
+
+    #[test]
+    fn status_classes() {
+        assert_eq!(
+            StatusClass::from(StatusCode::CONTINUE),
+            StatusClass::Informational
+        );
+        assert_eq!(StatusClass::from(StatusCode::CREATED), StatusClass::Success);
+        assert_eq!(
+            StatusClass::from(StatusCode::MOVED_PERMANENTLY),
+            StatusClass::Redirection
+        );
+        assert_eq!(
+            StatusClass::from(StatusCode::NOT_FOUND),
+            StatusClass::ClientError
+        );
+        assert_eq!(
+            StatusClass::from(StatusCode::BAD_GATEWAY),
+            StatusClass::ServerError
+        );
+    }
+
+    #[test]
+    fn only_client_and_server_errors_are_errors() {
+        assert!(!StatusClass::Success.is_error());
+        assert!(!StatusClass::Redirection.is_error());
+        assert!(StatusClass::ClientError.is_error());
+        assert!(StatusClass::ServerError.is_error());
+    }
+
+    #[test]
+    fn new_response_defaults_to_ok() {
+        let response = TimedResponse::new(String::from("alive"), Duration::from_millis(3));
+
+        assert_eq!(response.text(), "alive");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.status_class(), StatusClass::Success);
+        assert!(response.headers().is_empty());
+        assert_eq!(response.body_size(), 5);
+    }
+
+    #[test]
+    fn response_with_status_headers_and_size() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "text/plain".parse().unwrap());
+
+        let response = TimedResponse::new(String::from("oops"), Duration::from_millis(3))
+            .with_status(StatusCode::SERVICE_UNAVAILABLE)
+            .with_headers(headers.clone())
+            .with_body_size(10);
+
+        assert_eq!(response.status_class(), StatusClass::ServerError);
+        assert_eq!(response.headers(), &headers);
+        assert_eq!(response.body_size(), 10);
+    }
+
     #[test]
     fn display_simple_response() {
         assert_eq!(
@@ -84,4 +383,21 @@ mod test {
             String::from("Reponse: 'response'\n Response_time: '10000.000002'")
         )
     }
+
+    #[test]
+    fn response_time_includes_body_transfer() {
+        let response =
+            TimedResponse::new(String::from("alive"), Duration::from_millis(5)).with_timings(
+                ResponseTimings::new(Duration::from_millis(20), Duration::from_millis(7)),
+            );
+
+        assert_eq!(response.response_time(), Duration::from_millis(27));
+        assert_eq!(
+            response.timings().phases().collect::<Vec<_>>(),
+            vec![
+                (Phase::Headers, Duration::from_millis(20)),
+                (Phase::Body, Duration::from_millis(7)),
+            ]
+        );
+    }
 }
@@ -1,3 +1,13 @@
+pub mod body;
+mod builder;
+pub mod check;
+pub mod connection_options;
 pub mod definition;
+mod dns;
+pub mod extract;
 pub mod interface;
+pub mod reqwest_async;
 pub mod reqwest_based;
+#[cfg(test)]
+mod test_server;
+pub mod variables;
@@ -0,0 +1,174 @@
+use crate::request::builder::{apply_definition, url};
+use crate::request::connection_options::{async_client, ConnectionOptions};
+use crate::request::definition::{Auth, ResolvedRequest};
+use crate::request::interface::{AsyncHTTPClient, RequestError, ResponseTimings, TimedResponse};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct AsyncReqwestConnection {
+    client: reqwest::Client,
+    host: String,
+    default_headers: Vec<(String, String)>,
+    default_auth: Option<Auth>,
+    options: ConnectionOptions,
+}
+
+impl AsyncReqwestConnection {
+    pub fn new(host: &str) -> Self {
+        Self {
+            client: async_client(&ConnectionOptions::default()),
+            host: host.to_string(),
+            default_headers: vec![],
+            default_auth: None,
+            options: ConnectionOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: ConnectionOptions) -> Self {
+        self.client = async_client(&options);
+        self.options = options;
+        self
+    }
+
+    pub fn with_default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_default_auth(mut self, auth: Auth) -> Self {
+        self.default_auth = Some(auth);
+        self
+    }
+
+    pub fn prepare(&self, request: &ResolvedRequest<'_>) -> Result<reqwest::Request, RequestError> {
+        let builder = self
+            .client
+            .request(request.method().clone(), url(&self.host, request));
+        Ok(apply_definition(
+            builder,
+            &self.default_headers,
+            self.default_auth.as_ref(),
+            request,
+        )?
+        .build()?)
+    }
+}
+
+impl AsyncHTTPClient for AsyncReqwestConnection {
+    async fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+        let request = self.prepare(request)?;
+        let request_send = Instant::now();
+        let response = self.client.execute(request).await?;
+        let headers_time = request_send.elapsed();
+
+        Ok(into_timed_response(response, headers_time, Instant::now()).await?)
+    }
+
+    fn for_user(&self, _user: usize) -> Option<Self> {
+        self.options.per_user().then(|| Self {
+            client: async_client(&self.options),
+            ..self.clone()
+        })
+    }
+}
+
+/// Times the body from `headers_received` until it is read.
+async fn into_timed_response(
+    response: reqwest::Response,
+    headers_time: Duration,
+    headers_received: Instant,
+) -> Result<TimedResponse, reqwest::Error> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    let timings = ResponseTimings::new(headers_time, headers_received.elapsed());
+
+    Ok(
+        TimedResponse::new(String::from_utf8_lossy(&body).into_owned(), timings.total())
+            .with_timings(timings)
+            .with_status(status)
+            .with_headers(headers)
+            .with_body_size(body.len()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::body::Body;
+    use crate::request::definition::RequestDefinition;
+    use crate::request::test_server::{serve_once, unused_port};
+
+    #[test]
+    fn prepare_request_like_blocking_connection() {
+        let request = AsyncReqwestConnection::new("http://localhost")
+            .with_default_header("user-agent", "loadtest")
+            .with_default_auth(Auth::Bearer(String::from("secret")))
+            .prepare(
+                &RequestDefinition::post("tsp", Body::text("six cities"))
+                    .with_query("page", "2")
+                    .into(),
+            )
+            .unwrap();
+
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(request.url().as_str(), "http://localhost/tsp?page=2");
+        assert_eq!(request.headers()["user-agent"], "loadtest");
+        assert_eq!(request.headers()["authorization"], "Bearer secret");
+        assert_eq!(
+            request.headers()["content-type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            request.body().unwrap().as_bytes(),
+            Some(b"six cities".as_slice())
+        );
+    }
+
+    #[tokio::test]
+    async fn response_carries_status_headers_and_size() {
+        let host = serve_once(
+            "HTTP/1.1 201 Created\r\ncontent-length: 5\r\nx-solver: genetic\r\n\r\nalive",
+        );
+
+        let response = AsyncReqwestConnection::new(&host)
+            .send(&RequestDefinition::get("alive").into())
+            .await
+            .unwrap();
+
+        assert_eq!(response.text(), "alive");
+        assert_eq!(response.status(), http::StatusCode::CREATED);
+        assert_eq!(response.headers()["x-solver"], "genetic");
+        assert_eq!(response.body_size(), 5);
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_connect_error() {
+        let host = format!("http://127.0.0.1:{}", unused_port());
+
+        assert_eq!(
+            AsyncReqwestConnection::new(&host)
+                .send(&RequestDefinition::get("alive").into())
+                .await,
+            Err(RequestError::Connect)
+        );
+    }
+
+    #[tokio::test]
+    async fn request_timeout_is_timeout_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+
+        assert_eq!(
+            AsyncReqwestConnection::new(&host)
+                .send(
+                    &RequestDefinition::get("tsp")
+                        .with_timeout(Duration::from_millis(50))
+                        .into()
+                )
+                .await,
+            Err(RequestError::Timeout)
+        );
+    }
+}
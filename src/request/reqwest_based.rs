@@ -1,11 +1,16 @@
-use crate::request::interface::{HTTPClient, RequestError, TimedResponse};
-use erased_serde::Serialize;
+use crate::request::builder::{apply_definition, url};
+use crate::request::connection_options::{blocking_client, ConnectionOptions};
+use crate::request::definition::{Auth, ResolvedRequest};
+use crate::request::interface::{HTTPClient, RequestError, ResponseTimings, TimedResponse};
 use mockall::automock;
 use std::time::{Duration, Instant};
 #[derive(Debug, Clone)]
-pub struct ReqwestConnection<'a> {
+pub struct ReqwestConnection {
     client: reqwest::blocking::Client,
-    host: &'a str,
+    host: String,
+    default_headers: Vec<(String, String)>,
+    default_auth: Option<Auth>,
+    options: ConnectionOptions,
 }
 
 #[automock]
@@ -25,54 +30,73 @@ impl Client for reqwest::blocking::Client {
     }
 }
 
-impl<'a> ReqwestConnection<'a> {
-    pub fn new(host: &'a str) -> Self {
+impl ReqwestConnection {
+    pub fn new(host: &str) -> Self {
         Self {
-            client: reqwest::blocking::Client::new(),
-            host,
+            client: blocking_client(&ConnectionOptions::default()),
+            host: host.to_string(),
+            default_headers: vec![],
+            default_auth: None,
+            options: ConnectionOptions::default(),
         }
     }
-}
 
-impl HTTPClient for ReqwestConnection<'_> {
-    fn get(&self, endpoint: &'_ str) -> Result<TimedResponse, RequestError> {
-        let request = build_get_request(&self.client, self.host, endpoint)?;
-        let (response, response_time) = send_and_time_request(&self.client, request)?;
-        let response_text = extract_text(response)?;
+    pub fn with_options(mut self, options: ConnectionOptions) -> Self {
+        self.client = blocking_client(&options);
+        self.options = options;
+        self
+    }
 
-        Ok(TimedResponse::new(response_text, response_time))
+    pub fn with_default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .push((name.to_string(), value.to_string()));
+        self
     }
-    fn post<'a>(
-        &self,
-        endpoint: &'a str,
-        body: &'a dyn Serialize,
-    ) -> Result<TimedResponse, RequestError> {
-        let request = build_post_request(&self.client, self.host, endpoint, body)?;
-        let (response, response_time) = send_and_time_request(&self.client, request)?;
-        let response_text = extract_text(response)?;
 
-        Ok(TimedResponse::new(response_text, response_time))
+    pub fn with_default_auth(mut self, auth: Auth) -> Self {
+        self.default_auth = Some(auth);
+        self
+    }
+
+    pub fn prepare(
+        &self,
+        request: &ResolvedRequest<'_>,
+    ) -> Result<reqwest::blocking::Request, RequestError> {
+        build_request(
+            &self.client,
+            &self.host,
+            &self.default_headers,
+            self.default_auth.as_ref(),
+            request,
+        )
     }
 }
 
-fn build_post_request(
-    client: &reqwest::blocking::Client,
-    host: &'_ str,
-    endpoint: &'_ str,
-    body: &'_ dyn Serialize,
-) -> Result<reqwest::blocking::Request, reqwest::Error> {
-    client
-        .post(format!("{}/{}", host, endpoint))
-        .json(body)
-        .build()
+impl HTTPClient for ReqwestConnection {
+    fn send(&self, request: &ResolvedRequest<'_>) -> Result<TimedResponse, RequestError> {
+        let request = self.prepare(request)?;
+        let (response, headers_time) = send_and_time_request(&self.client, request)?;
+
+        Ok(into_timed_response(response, headers_time, Instant::now())?)
+    }
+
+    fn for_user(&self, _user: usize) -> Option<Self> {
+        self.options.per_user().then(|| Self {
+            client: blocking_client(&self.options),
+            ..self.clone()
+        })
+    }
 }
 
-fn build_get_request(
+fn build_request(
     client: &reqwest::blocking::Client,
-    host: &'_ str,
-    endpoint: &'_ str,
-) -> Result<reqwest::blocking::Request, reqwest::Error> {
-    client.get(format!("{}/{}", host, endpoint)).build()
+    host: &str,
+    default_headers: &[(String, String)],
+    default_auth: Option<&Auth>,
+    request: &ResolvedRequest<'_>,
+) -> Result<reqwest::blocking::Request, RequestError> {
+    let builder = client.request(request.method().clone(), url(host, request));
+    Ok(apply_definition(builder, default_headers, default_auth, request)?.build()?)
 }
 
 fn send_and_time_request(
@@ -86,16 +110,39 @@ fn send_and_time_request(
     Ok((response, reponse_time))
 }
 
-fn extract_text(response: reqwest::blocking::Response) -> Result<String, reqwest::Error> {
-    response.text()
+/// Times the body from `headers_received` until it is read.
+fn into_timed_response(
+    response: reqwest::blocking::Response,
+    headers_time: Duration,
+    headers_received: Instant,
+) -> Result<TimedResponse, reqwest::Error> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes()?;
+    let timings = ResponseTimings::new(headers_time, headers_received.elapsed());
+
+    Ok(
+        TimedResponse::new(String::from_utf8_lossy(&body).into_owned(), timings.total())
+            .with_timings(timings)
+            .with_status(status)
+            .with_headers(headers)
+            .with_body_size(body.len()),
+    )
 }
 
 #[cfg(test)]
 mod test {
+    use crate::request::definition::RequestDefinition;
     use serde::Serialize;
 
     use super::*;
+    use crate::request::body::{Body, Part};
+    use crate::request::connection_options::ConnectionOptions;
     use crate::request::interface::to_millisecond;
+    use crate::request::test_server::{
+        serve_gated, serve_keep_alive, serve_once, serve_self_signed_tls, unused_port,
+    };
+    use std::sync::atomic::Ordering;
 
     fn assert_request_same_method_url(
         request_1: &reqwest::blocking::Request,
@@ -107,10 +154,12 @@ mod test {
 
     #[test]
     fn test_build_get_request() {
-        let request = build_get_request(
+        let request = build_request(
             &reqwest::blocking::Client::new(),
             "http://localhost",
-            "test",
+            &[],
+            None,
+            &RequestDefinition::get("test").into(),
         )
         .unwrap();
 
@@ -122,20 +171,26 @@ mod test {
         assert_request_same_method_url(&request, &expected_request)
     }
 
-    #[derive(Serialize)]
-    struct TestContent<'a> {
-        message: &'a str,
+    #[derive(Clone, Serialize)]
+    struct TestContent {
+        message: &'static str,
     }
 
     #[test]
     fn test_build_post_request() {
-        let request = build_post_request(
+        let request = build_request(
             &reqwest::blocking::Client::new(),
             "http://localhost",
-            "test",
-            &TestContent {
-                message: "testing-message",
-            },
+            &[],
+            None,
+            &RequestDefinition::post(
+                "test",
+                Body::json(&TestContent {
+                    message: "testing-message",
+                })
+                .unwrap(),
+            )
+            .into(),
         )
         .unwrap();
 
@@ -151,6 +206,268 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_build_requests_for_other_methods() {
+        let body = TestContent {
+            message: "testing-message",
+        };
+        for (definition, method) in [
+            (
+                RequestDefinition::put("test", Body::json(&body).unwrap()),
+                http::Method::PUT,
+            ),
+            (
+                RequestDefinition::patch("test", Body::json(&body).unwrap()),
+                http::Method::PATCH,
+            ),
+            (RequestDefinition::delete("test"), http::Method::DELETE),
+            (RequestDefinition::head("test"), http::Method::HEAD),
+            (RequestDefinition::options("test"), http::Method::OPTIONS),
+        ] {
+            let request = build_request(
+                &reqwest::blocking::Client::new(),
+                "http://localhost",
+                &[],
+                None,
+                &ResolvedRequest::from(&definition),
+            )
+            .unwrap();
+
+            assert_request_same_method_url(
+                &request,
+                &reqwest::blocking::Request::new(
+                    method,
+                    reqwest::Url::parse("http://localhost/test").unwrap(),
+                ),
+            );
+            assert_eq!(request.body().is_some(), definition.body().is_some());
+        }
+    }
+
+    fn build_with_body(definition: &RequestDefinition) -> reqwest::blocking::Request {
+        build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &[],
+            None,
+            &ResolvedRequest::from(definition),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_requests_with_encoded_bodies() {
+        let content = TestContent { message: "hi" };
+        let mut cbor = vec![];
+        ciborium::ser::into_writer(&content, &mut cbor).unwrap();
+
+        for (body, content_type, data) in [
+            (
+                Body::text("plain"),
+                "text/plain; charset=utf-8",
+                b"plain".to_vec(),
+            ),
+            (
+                Body::bytes("image/png", vec![0x89, 0x50]),
+                "image/png",
+                vec![0x89, 0x50],
+            ),
+            (
+                Body::form(&[("name", "Steven"), ("city", "Zürich")]),
+                "application/x-www-form-urlencoded",
+                b"name=Steven&city=Z%C3%BCrich".to_vec(),
+            ),
+            (
+                Body::message_pack(&content).unwrap(),
+                "application/msgpack",
+                rmp_serde::to_vec_named(&content).unwrap(),
+            ),
+            (Body::cbor(&content).unwrap(), "application/cbor", cbor),
+        ] {
+            let request = build_with_body(&RequestDefinition::post("test", body));
+
+            assert_eq!(request.headers()["content-type"], content_type);
+            assert_eq!(request.body().unwrap().as_bytes(), Some(data.as_slice()));
+        }
+    }
+
+    #[test]
+    fn test_build_request_keeps_explicit_content_type() {
+        let request = build_with_body(
+            &RequestDefinition::post("test", Body::text("{}"))
+                .with_header("Content-Type", "application/vnd.tsp+json"),
+        );
+
+        assert_eq!(
+            request
+                .headers()
+                .get_all("content-type")
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["application/vnd.tsp+json"]
+        );
+    }
+
+    #[test]
+    fn test_build_request_keeps_default_content_type() {
+        let request = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &[(
+                String::from("content-type"),
+                String::from("application/vnd.tsp+json"),
+            )],
+            None,
+            &RequestDefinition::post("test", Body::text("{}")).into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            request
+                .headers()
+                .get_all("content-type")
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["application/vnd.tsp+json"]
+        );
+    }
+
+    #[test]
+    fn test_build_multipart_request() {
+        let mut request = build_with_body(&RequestDefinition::post(
+            "upload",
+            Body::Multipart(vec![
+                Part::text("description", "six cities"),
+                Part::bytes("file", b"1,2\n3,4".to_vec())
+                    .with_file_name("cities.csv")
+                    .with_content_type("text/csv"),
+            ]),
+        ));
+
+        assert!(request.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("multipart/form-data; boundary="));
+        let body = request.body_mut().as_mut().unwrap().buffer().unwrap();
+        let body = String::from_utf8_lossy(body);
+        assert!(
+            body.contains("filename=\"cities.csv\"\r\nContent-Type: text/csv\r\n\r\n1,2\n3,4\r\n")
+        );
+        assert!(body.contains("six cities"));
+    }
+
+    #[test]
+    fn test_invalid_multipart_content_type() {
+        let error = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &[],
+            None,
+            &RequestDefinition::post(
+                "upload",
+                Body::Multipart(vec![
+                    Part::bytes("file", vec![]).with_content_type("not a mime type")
+                ]),
+            )
+            .into(),
+        )
+        .unwrap_err();
+
+        assert_eq!(error, RequestError::BodySerialization);
+    }
+
+    #[test]
+    fn test_build_request_with_headers_query_and_auth() {
+        let request = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &[],
+            None,
+            &RequestDefinition::get("tsp/jobs")
+                .with_header("x-request-id", "42")
+                .with_query("status", "done")
+                .with_query("page", "2")
+                .with_auth(Auth::Bearer(String::from("secret")))
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost/tsp/jobs?status=done&page=2"
+        );
+        assert_eq!(request.headers()["x-request-id"], "42");
+        assert_eq!(request.headers()["authorization"], "Bearer secret");
+    }
+
+    #[test]
+    fn test_build_request_with_basic_auth() {
+        let request = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &[],
+            None,
+            &RequestDefinition::get("test")
+                .with_auth(Auth::Basic {
+                    username: String::from("user"),
+                    password: Some(String::from("pass")),
+                })
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(request.headers()["authorization"], "Basic dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn test_build_request_with_client_defaults() {
+        let default_headers = [
+            (String::from("user-agent"), String::from("loadtest")),
+            (String::from("x-tenant"), String::from("default")),
+        ];
+        let default_auth = Auth::Bearer(String::from("default-token"));
+
+        let with_defaults = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &default_headers,
+            Some(&default_auth),
+            &RequestDefinition::get("test").into(),
+        )
+        .unwrap();
+        let overriding_defaults = build_request(
+            &reqwest::blocking::Client::new(),
+            "http://localhost",
+            &default_headers,
+            Some(&default_auth),
+            &RequestDefinition::get("test")
+                .with_header("X-Tenant", "tsp")
+                .with_auth(Auth::Bearer(String::from("user-token")))
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(with_defaults.headers()["user-agent"], "loadtest");
+        assert_eq!(with_defaults.headers()["x-tenant"], "default");
+        assert_eq!(
+            with_defaults.headers()["authorization"],
+            "Bearer default-token"
+        );
+        assert_eq!(overriding_defaults.headers()["user-agent"], "loadtest");
+        assert_eq!(
+            overriding_defaults
+                .headers()
+                .get_all("x-tenant")
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["tsp"]
+        );
+        assert_eq!(
+            overriding_defaults.headers()["authorization"],
+            "Bearer user-token"
+        );
+    }
+
     #[test]
     fn test_send_and_time_request() {
         let example_request = reqwest::blocking::Request::new(
@@ -173,4 +490,210 @@ mod test {
         assert!(to_millisecond(actual_response_time) > 100.0);
         assert_eq!(actual_response.text().unwrap(), String::from("body text"))
     }
+
+    #[test]
+    fn response_carries_status_headers_and_size() {
+        let host = serve_once(
+            "HTTP/1.1 201 Created\r\ncontent-length: 5\r\nx-solver: genetic\r\n\r\nalive",
+        );
+
+        let response = ReqwestConnection::new(&host)
+            .send(&RequestDefinition::get("alive").into())
+            .unwrap();
+
+        assert_eq!(response.text(), "alive");
+        assert_eq!(response.status(), http::StatusCode::CREATED);
+        assert_eq!(response.headers()["x-solver"], "genetic");
+        assert_eq!(response.body_size(), 5);
+    }
+
+    #[test]
+    fn body_is_timed_after_headers() {
+        let (open_gate, gate) = std::sync::mpsc::channel();
+        let delay = Duration::from_millis(100);
+        let host = serve_gated(
+            "HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nal",
+            "ive",
+            gate,
+            delay,
+        );
+        let connection = ReqwestConnection::new(&host);
+        let request = connection
+            .prepare(&RequestDefinition::get("alive").into())
+            .unwrap();
+
+        // The rest of the body is only sent once the headers are in, so its delay
+        // falls in the body phase however slowly either side runs.
+        let (response, headers_time) = send_and_time_request(&connection.client, request).unwrap();
+        let headers_received = Instant::now();
+        open_gate.send(()).unwrap();
+        let response = into_timed_response(response, headers_time, headers_received).unwrap();
+        let timings = response.timings();
+
+        assert_eq!(response.text(), "alive");
+        assert_eq!(timings.headers(), headers_time);
+        assert!(timings.body() >= delay);
+        assert_eq!(response.response_time(), timings.total());
+    }
+
+    #[test]
+    fn unsuccessful_status_is_response() {
+        let host =
+            serve_once("HTTP/1.1 500 Internal Server Error\r\ncontent-length: 4\r\n\r\noops");
+
+        let response = ReqwestConnection::new(&host)
+            .send(&RequestDefinition::get("tsp").into())
+            .unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.text(), "oops");
+    }
+
+    #[test]
+    fn refused_connection_is_connect_error() {
+        let host = format!("http://127.0.0.1:{}", unused_port());
+
+        assert_eq!(
+            ReqwestConnection::new(&host).send(&RequestDefinition::get("alive").into()),
+            Err(RequestError::Connect)
+        );
+    }
+
+    #[test]
+    fn unknown_host_is_dns_error() {
+        assert_eq!(
+            ReqwestConnection::new("http://does-not-exist.invalid")
+                .send(&RequestDefinition::get("alive").into()),
+            Err(RequestError::Dns)
+        );
+    }
+
+    #[test]
+    fn plain_text_server_is_tls_error() {
+        let host = serve_once("HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nalive");
+
+        assert_eq!(
+            ReqwestConnection::new(&host.replace("http://", "https://"))
+                .send(&RequestDefinition::get("alive").into()),
+            Err(RequestError::Tls)
+        );
+    }
+
+    #[test]
+    fn self_signed_certificate_is_tls_error() {
+        assert_eq!(
+            ReqwestConnection::new(&serve_self_signed_tls())
+                .send(&RequestDefinition::get("alive").into()),
+            Err(RequestError::Tls)
+        );
+    }
+
+    #[test]
+    fn unanswered_request_is_timeout() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let error = client
+            .get(format!("http://{}/", listener.local_addr().unwrap()))
+            .send()
+            .unwrap_err();
+
+        assert_eq!(RequestError::from(error), RequestError::Timeout);
+    }
+
+    #[test]
+    fn connection_timeout_is_timeout_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let connection =
+            ReqwestConnection::new(&format!("http://{}", listener.local_addr().unwrap()))
+                .with_options(ConnectionOptions::default().with_timeout(Duration::from_millis(50)));
+
+        assert_eq!(
+            connection.send(&RequestDefinition::get("tsp").into()),
+            Err(RequestError::Timeout)
+        );
+    }
+
+    #[test]
+    fn request_timeout_overrides_connection_timeout() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let connection =
+            ReqwestConnection::new(&format!("http://{}", listener.local_addr().unwrap()))
+                .with_options(ConnectionOptions::default().with_timeout(Duration::from_secs(60)));
+        let started = Instant::now();
+
+        assert_eq!(
+            connection.send(
+                &RequestDefinition::get("tsp")
+                    .with_timeout(Duration::from_millis(50))
+                    .into()
+            ),
+            Err(RequestError::Timeout)
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn prepared_request_carries_timeout() {
+        let request = ReqwestConnection::new("http://localhost")
+            .prepare(
+                &RequestDefinition::get("tsp")
+                    .with_timeout(Duration::from_millis(750))
+                    .into(),
+            )
+            .unwrap();
+
+        assert_eq!(request.timeout(), Some(&Duration::from_millis(750)));
+    }
+
+    const KEEP_ALIVE_RESPONSE: &str = "HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nalive";
+
+    #[test]
+    fn connections_are_reused() {
+        let (host, connections) = serve_keep_alive(KEEP_ALIVE_RESPONSE);
+        let connection = ReqwestConnection::new(&host);
+
+        for _ in 0..3 {
+            connection
+                .send(&RequestDefinition::get("alive").into())
+                .unwrap();
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn every_request_connects_without_keep_alive() {
+        let (host, connections) = serve_keep_alive(KEEP_ALIVE_RESPONSE);
+        let connection = ReqwestConnection::new(&host)
+            .with_options(ConnectionOptions::default().with_keep_alive(false));
+
+        for _ in 0..3 {
+            connection
+                .send(&RequestDefinition::get("alive").into())
+                .unwrap();
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn per_user_clients_connect_separately() {
+        let (host, connections) = serve_keep_alive(KEEP_ALIVE_RESPONSE);
+        let shared = ReqwestConnection::new(&host);
+        let per_user = ReqwestConnection::new(&host)
+            .with_options(ConnectionOptions::default().with_per_user(true));
+
+        assert!(shared.for_user(0).is_none());
+        for user in 0..2 {
+            let own = per_user.for_user(user).unwrap();
+            own.send(&RequestDefinition::get("alive").into()).unwrap();
+            own.send(&RequestDefinition::get("alive").into()).unwrap();
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Values a virtual user carries from one request to the next, substituted for
+/// `{{name}}` placeholders. Placeholders without a value are left as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Variables {
+    values: HashMap<String, String>,
+}
+
+impl Variables {
+    pub fn set(&mut self, name: &str, value: String) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn substitute<'a>(&self, template: &'a str) -> Cow<'a, str> {
+        self.substitute_with(template, |_, value, substituted| {
+            substituted.push_str(value)
+        })
+    }
+
+    /// Substitutes values escaped inside JSON strings and as they are elsewhere, so a
+    /// placeholder outside quotes can stand for a number, array or object.
+    pub fn substitute_json<'a>(&self, template: &'a str) -> Cow<'a, str> {
+        let mut string = JsonString::default();
+        self.substitute_with(template, |preceding, value, substituted| {
+            string.read(preceding);
+            if string.inside {
+                let quoted = serde_json::Value::from(value).to_string();
+                substituted.push_str(&quoted[1..quoted.len() - 1]);
+            } else {
+                substituted.push_str(value);
+            }
+        })
+    }
+
+    /// `insert` receives the template since the previous placeholder it was called for,
+    /// the value and the output.
+    fn substitute_with<'a>(
+        &self,
+        template: &'a str,
+        mut insert: impl FnMut(&str, &str, &mut String),
+    ) -> Cow<'a, str> {
+        if self.values.is_empty() || !template.contains("{{") {
+            return Cow::Borrowed(template);
+        }
+        let mut substituted = String::with_capacity(template.len());
+        let mut rest = template;
+        let mut read = 0;
+        while let Some(start) = rest.find("{{") {
+            let Some(length) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let placeholder = &rest[start..start + 2 + length + 2];
+            let placeholder_start = template.len() - rest.len() + start;
+            substituted.push_str(&rest[..start]);
+            match self.get(placeholder[2..placeholder.len() - 2].trim()) {
+                Some(value) => {
+                    insert(&template[read..placeholder_start], value, &mut substituted);
+                    read = placeholder_start;
+                }
+                None => substituted.push_str(placeholder),
+            }
+            rest = &rest[start + placeholder.len()..];
+        }
+        substituted.push_str(rest);
+        Cow::Owned(substituted)
+    }
+}
+
+pub(crate) fn has_placeholder(text: &str) -> bool {
+    text.contains("{{")
+}
+
+/// Whether the JSON read so far ends inside a string.
+#[derive(Default)]
+struct JsonString {
+    inside: bool,
+    escaped: bool,
+}
+
+impl JsonString {
+    fn read(&mut self, json: &str) {
+        for character in json.chars() {
+            match character {
+                _ if self.escaped => self.escaped = false,
+                '\\' if self.inside => self.escaped = true,
+                '"' => self.inside = !self.inside,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn variables() -> Variables {
+        let mut variables = Variables::default();
+        variables.set("job_id", String::from("42"));
+        variables.set("note", String::from(r#"say "hi""#));
+        variables
+    }
+
+    #[test]
+    fn substitutes_known_placeholders() {
+        assert_eq!(
+            variables().substitute("/tsp/jobs/{{job_id}}?again={{ job_id }}"),
+            "/tsp/jobs/42?again=42"
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_placeholders() {
+        assert_eq!(
+            variables().substitute("{{missing}}/{{job_id}}/{{open"),
+            "{{missing}}/42/{{open"
+        );
+    }
+
+    #[test]
+    fn borrows_without_placeholders() {
+        assert!(matches!(variables().substitute("/alive"), Cow::Borrowed(_)));
+        assert!(matches!(
+            Variables::default().substitute("{{job_id}}"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(
+            variables().substitute_json(r#"{"note": "{{note}}", "job": {{job_id}}}"#),
+            r#"{"note": "say \"hi\"", "job": 42}"#
+        );
+    }
+
+    #[test]
+    fn tracks_json_strings_across_placeholders() {
+        assert_eq!(
+            variables().substitute_json(
+                r#"{"notes": "{{note}}, \"{{note}}\"", "job": {{job_id}}, "id": "{{job_id}}"}"#
+            ),
+            r#"{"notes": "say \"hi\", \"say \"hi\"\"", "job": 42, "id": "42"}"#
+        );
+        assert_eq!(
+            variables().substitute_json(r#"{"missing": "{{missing}}", "raw": {{note}}}"#),
+            r#"{"missing": "{{missing}}", "raw": say "hi"}"#
+        );
+    }
+
+    #[test]
+    fn inserts_json_values_outside_strings() {
+        let mut variables = variables();
+        variables.set("tags", String::from(r#"["a", "b"]"#));
+
+        assert_eq!(
+            variables.substitute_json(r#"{"quoted": "\"{{tags}}", "tags": {{tags}}}"#),
+            r#"{"quoted": "\"[\"a\", \"b\"]", "tags": ["a", "b"]}"#
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    Read { path: PathBuf, message: String },
+    UnsupportedFormat(PathBuf),
+    Parse(String),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Read { path, message } => {
+                write!(f, "could not read '{}': {}", path.display(), message)
+            }
+            ScenarioError::UnsupportedFormat(path) => write!(
+                f,
+                "unsupported scenario format '{}', expected .yaml, .yml, .toml or .json",
+                path.display()
+            ),
+            ScenarioError::Parse(message) => write!(f, "invalid scenario: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
@@ -0,0 +1,53 @@
+use crate::scenario::error::ScenarioError;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Result<Self, ScenarioError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml" | "yml") => Ok(Format::Yaml),
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            _ => Err(ScenarioError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    pub(crate) fn parse<T: DeserializeOwned>(&self, contents: &str) -> Result<T, ScenarioError> {
+        match self {
+            Format::Yaml => serde_yaml::with::singleton_map_recursive::deserialize(
+                serde_yaml::Deserializer::from_str(contents),
+            )
+            .map_err(|error| error.to_string()),
+            Format::Toml => toml::from_str(contents).map_err(|error| error.to_string()),
+            Format::Json => serde_json::from_str(contents).map_err(|error| error.to_string()),
+        }
+        .map_err(ScenarioError::Parse)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            ["a.yaml", "a.yml", "a.toml", "a.json"]
+                .iter()
+                .map(|path| Format::from_path(Path::new(path)).unwrap())
+                .collect::<Vec<_>>(),
+            vec![Format::Yaml, Format::Yaml, Format::Toml, Format::Json]
+        );
+        assert_eq!(
+            Format::from_path(Path::new("scenario.ini")),
+            Err(ScenarioError::UnsupportedFormat("scenario.ini".into()))
+        );
+    }
+}
@@ -0,0 +1,987 @@
+use crate::load_test::executor::Executor;
+use crate::load_test::feeder::{FeedOrder, Feeder, FeederError, OnExhausted, Row};
+use crate::load_test::profile::{LoadProfile, Stage};
+use crate::load_test::request_mix::RequestMix;
+use crate::load_test::run_mode::RunMode;
+use crate::load_test::think_time::ThinkTime;
+use crate::request::body::Body;
+use crate::request::check::Check;
+use crate::request::connection_options::ConnectionOptions;
+use crate::request::definition::{Auth, RequestDefinition};
+use crate::request::extract::Extractor;
+use crate::request::reqwest_async::AsyncReqwestConnection;
+use crate::request::reqwest_based::ReqwestConnection;
+use crate::scenario::error::ScenarioError;
+use crate::scenario::format::Format;
+use crate::statistics::threshold::{matches_endpoint, Threshold};
+use crate::tsp_specific::checks::visits_every_city_once;
+use crate::LoadTest;
+use bytes::Bytes;
+use http::{Method, StatusCode};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const JSON_VALUES_SERIALIZE: &str = "JSON values serialize to every body format.";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    host: String,
+    #[serde(default = "one")]
+    virtual_users: usize,
+    #[serde(default)]
+    run: RunSpec,
+    #[serde(default)]
+    executor: Executor,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    auth: Option<Auth>,
+    #[serde(default)]
+    connection: ConnectionOptions,
+    #[serde(default)]
+    think_time: Option<ThinkTimeSpec>,
+    #[serde(default)]
+    iteration_think_time: Option<ThinkTimeSpec>,
+    #[serde(
+        default,
+        rename = "pacing_ms",
+        deserialize_with = "optional_milliseconds"
+    )]
+    pacing: Option<Duration>,
+    #[serde(default)]
+    request_mix: RequestMix,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    feeders: Vec<FeederSpec>,
+    requests: Vec<RequestSpec>,
+    #[serde(default)]
+    thresholds: Vec<Threshold>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunSpec {
+    Iterations(usize),
+    #[serde(rename = "duration_secs")]
+    Duration(#[serde(deserialize_with = "seconds")] Duration),
+    Profile {
+        #[serde(default)]
+        start_users: usize,
+        stages: Vec<StageSpec>,
+    },
+}
+
+impl Default for RunSpec {
+    fn default() -> Self {
+        RunSpec::Iterations(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageSpec {
+    #[serde(rename = "duration_secs", deserialize_with = "seconds")]
+    duration: Duration,
+    target_users: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestSpec {
+    #[serde(default = "get", deserialize_with = "method")]
+    method: Method,
+    endpoint: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    query: BTreeMap<String, String>,
+    #[serde(default)]
+    auth: Option<Auth>,
+    #[serde(default)]
+    body: Option<BodySpec>,
+    #[serde(default)]
+    checks: Vec<CheckSpec>,
+    #[serde(
+        default,
+        rename = "timeout_ms",
+        deserialize_with = "optional_milliseconds"
+    )]
+    timeout: Option<Duration>,
+    #[serde(default)]
+    think_time: Option<ThinkTimeSpec>,
+    #[serde(default = "one_weight")]
+    weight: u32,
+    #[serde(default)]
+    extract: BTreeMap<String, ExtractSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractSpec {
+    JsonPointer(String),
+    Regex(String),
+    Header(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThinkTimeSpec {
+    #[serde(rename = "constant_ms")]
+    Constant(#[serde(deserialize_with = "milliseconds")] Duration),
+    Uniform {
+        #[serde(rename = "min_ms", deserialize_with = "milliseconds")]
+        min: Duration,
+        #[serde(rename = "max_ms", deserialize_with = "milliseconds")]
+        max: Duration,
+    },
+    Normal {
+        #[serde(rename = "mean_ms", deserialize_with = "milliseconds")]
+        mean: Duration,
+        #[serde(rename = "std_dev_ms", deserialize_with = "milliseconds")]
+        std_dev: Duration,
+    },
+    Exponential {
+        #[serde(rename = "mean_ms", deserialize_with = "milliseconds")]
+        mean: Duration,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeederSpec {
+    path: PathBuf,
+    #[serde(default)]
+    order: FeedOrder,
+    #[serde(default)]
+    on_exhausted: OnExhausted,
+    #[serde(skip)]
+    rows: Vec<Row>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodySpec {
+    Json(serde_json::Value),
+    JsonTemplate(String),
+    Text(String),
+    Form(BTreeMap<String, String>),
+    MessagePack(serde_json::Value),
+    Cbor(serde_json::Value),
+    File {
+        path: PathBuf,
+        #[serde(default = "octet_stream")]
+        content_type: String,
+        #[serde(skip)]
+        data: Bytes,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckSpec {
+    Status(#[serde(deserialize_with = "status")] StatusCode),
+    BodyContains(String),
+    JsonPointerEquals {
+        pointer: String,
+        value: serde_json::Value,
+    },
+    #[serde(rename = "max_latency_ms")]
+    MaxLatency(#[serde(deserialize_with = "milliseconds")] Duration),
+    /// The tour at `pointer`, the whole body by default, visits each of the cities once.
+    TourVisitsAllCities {
+        cities: usize,
+        #[serde(default)]
+        pointer: String,
+    },
+}
+
+impl Scenario {
+    pub fn from_path(path: &Path) -> Result<Self, ScenarioError> {
+        let format = Format::from_path(path)?;
+        let contents = read(path)?;
+        Self::parse(
+            &contents,
+            format,
+            path.parent().unwrap_or_else(|| Path::new("")),
+        )
+    }
+
+    /// Body and feeder files are resolved relative to `base_dir` and read once while
+    /// parsing.
+    pub fn parse(contents: &str, format: Format, base_dir: &Path) -> Result<Self, ScenarioError> {
+        let mut scenario: Scenario = format.parse(contents)?;
+        if scenario.requests.is_empty() {
+            return Err(ScenarioError::Parse(String::from(
+                "at least one request is required",
+            )));
+        }
+        if scenario.request_mix == RequestMix::Weighted
+            && scenario.requests.iter().all(|request| request.weight == 0)
+        {
+            return Err(ScenarioError::Parse(String::from(
+                "a weighted request mix needs a request with a weight above zero",
+            )));
+        }
+        for feeder in scenario.feeders.iter_mut() {
+            feeder.rows = Feeder::from_path(&base_dir.join(&feeder.path))
+                .map_err(|error| match error {
+                    FeederError::Read { path, message } => ScenarioError::Read { path, message },
+                    error => ScenarioError::Parse(error.to_string()),
+                })?
+                .rows()
+                .to_vec();
+        }
+        for request in scenario.requests.iter_mut() {
+            for extract in request.extract.values() {
+                if let ExtractSpec::Regex(regex) = extract {
+                    Regex::new(regex).map_err(|error| {
+                        ScenarioError::Parse(format!("invalid regex '{}': {}", regex, error))
+                    })?;
+                }
+            }
+            if let Some(BodySpec::File { path, data, .. }) = request.body.as_mut() {
+                *data = fs::read(base_dir.join(&*path))
+                    .map(Bytes::from)
+                    .map_err(|error| ScenarioError::Read {
+                        path: base_dir.join(&*path),
+                        message: error.to_string(),
+                    })?;
+            }
+        }
+        for endpoint in scenario.thresholds.iter().filter_map(Threshold::endpoint) {
+            if !scenario.requests.iter().any(|request| {
+                matches_endpoint(
+                    endpoint,
+                    &format!("{} {}", request.method, request.endpoint),
+                )
+            }) {
+                return Err(ScenarioError::Parse(format!(
+                    "threshold endpoint '{}' matches no request",
+                    endpoint
+                )));
+            }
+        }
+        Ok(scenario)
+    }
+
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    pub fn with_virtual_users(mut self, virtual_users: usize) -> Self {
+        self.virtual_users = virtual_users;
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.run = RunSpec::Iterations(iterations);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.run = RunSpec::Duration(duration);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.connection = self.connection.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn virtual_users(&self) -> usize {
+        self.virtual_users
+    }
+
+    pub fn run_mode(&self) -> RunMode {
+        match &self.run {
+            RunSpec::Iterations(iterations) => RunMode::Iterations(*iterations),
+            RunSpec::Duration(duration) => RunMode::Duration(*duration),
+            RunSpec::Profile {
+                start_users,
+                stages,
+            } => RunMode::Profile(LoadProfile::new(
+                *start_users,
+                stages
+                    .iter()
+                    .map(|stage| Stage::new(stage.duration, stage.target_users))
+                    .collect(),
+            )),
+        }
+    }
+
+    pub fn executor(&self) -> Executor {
+        self.executor
+    }
+
+    pub fn thresholds(&self) -> &[Threshold] {
+        &self.thresholds
+    }
+
+    pub fn think_time(&self) -> Option<ThinkTime> {
+        self.think_time.as_ref().map(ThinkTimeSpec::think_time)
+    }
+
+    pub fn iteration_think_time(&self) -> Option<ThinkTime> {
+        self.iteration_think_time
+            .as_ref()
+            .map(ThinkTimeSpec::think_time)
+    }
+
+    pub fn pacing(&self) -> Option<Duration> {
+        self.pacing
+    }
+
+    pub fn request_mix(&self) -> RequestMix {
+        self.request_mix
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn connection_options(&self) -> &ConnectionOptions {
+        &self.connection
+    }
+
+    pub fn connection(&self) -> ReqwestConnection {
+        let connection = self.headers.iter().fold(
+            ReqwestConnection::new(&self.host).with_options(self.connection.clone()),
+            |connection, (name, value)| connection.with_default_header(name, value),
+        );
+        match &self.auth {
+            Some(auth) => connection.with_default_auth(auth.clone()),
+            None => connection,
+        }
+    }
+
+    pub fn async_connection(&self) -> AsyncReqwestConnection {
+        let connection = self.headers.iter().fold(
+            AsyncReqwestConnection::new(&self.host).with_options(self.connection.clone()),
+            |connection, (name, value)| connection.with_default_header(name, value),
+        );
+        match &self.auth {
+            Some(auth) => connection.with_default_auth(auth.clone()),
+            None => connection,
+        }
+    }
+
+    pub fn request_definitions(&self) -> Vec<RequestDefinition> {
+        self.requests
+            .iter()
+            .map(RequestSpec::request_definition)
+            .collect()
+    }
+
+    pub fn load_test<R>(&self, connection: Arc<R>) -> LoadTest<R> {
+        let mut load_test = LoadTest::new(connection, self.request_definitions())
+            .with_virtual_users(self.virtual_users)
+            .with_run_mode(self.run_mode())
+            .with_executor(self.executor);
+        if let Some(think_time) = self.think_time() {
+            load_test = load_test.with_think_time(think_time);
+        }
+        if let Some(think_time) = self.iteration_think_time() {
+            load_test = load_test.with_iteration_think_time(think_time);
+        }
+        if let Some(pacing) = self.pacing {
+            load_test = load_test.with_pacing(pacing);
+        }
+        if let Some(seed) = self.seed {
+            load_test = load_test.with_seed(seed);
+        }
+        for feeder in &self.feeders {
+            load_test = load_test.with_feeder(feeder.feeder());
+        }
+        load_test.with_request_mix(self.request_mix)
+    }
+}
+
+impl RequestSpec {
+    fn request_definition(&self) -> RequestDefinition {
+        let mut definition = RequestDefinition::new(self.method.clone(), &self.endpoint);
+        for (name, value) in &self.headers {
+            definition = definition.with_header(name, value);
+        }
+        for (key, value) in &self.query {
+            definition = definition.with_query(key, value);
+        }
+        if let Some(auth) = &self.auth {
+            definition = definition.with_auth(auth.clone());
+        }
+        if let Some(body) = &self.body {
+            definition = definition.with_body(body.body());
+        }
+        if let Some(timeout) = self.timeout {
+            definition = definition.with_timeout(timeout);
+        }
+        if let Some(think_time) = &self.think_time {
+            definition = definition.with_think_time(think_time.think_time());
+        }
+        for (name, extract) in &self.extract {
+            definition = definition.with_extraction(name, extract.extractor());
+        }
+        definition = definition.with_weight(self.weight);
+        definition.with_checks(self.checks.iter().map(CheckSpec::check).collect())
+    }
+}
+
+impl FeederSpec {
+    fn feeder(&self) -> Feeder {
+        Feeder::new(self.rows.clone())
+            .with_order(self.order)
+            .with_on_exhausted(self.on_exhausted)
+    }
+}
+
+impl ExtractSpec {
+    fn extractor(&self) -> Extractor {
+        match self {
+            ExtractSpec::JsonPointer(pointer) => Extractor::JsonPointer(pointer.clone()),
+            ExtractSpec::Regex(regex) => {
+                Extractor::Regex(Regex::new(regex).expect("Regexes are validated when parsing."))
+            }
+            ExtractSpec::Header(name) => Extractor::Header(name.clone()),
+        }
+    }
+}
+
+impl ThinkTimeSpec {
+    fn think_time(&self) -> ThinkTime {
+        match *self {
+            ThinkTimeSpec::Constant(duration) => ThinkTime::Constant(duration),
+            ThinkTimeSpec::Uniform { min, max } => ThinkTime::Uniform { min, max },
+            ThinkTimeSpec::Normal { mean, std_dev } => ThinkTime::Normal { mean, std_dev },
+            ThinkTimeSpec::Exponential { mean } => ThinkTime::Exponential { mean },
+        }
+    }
+}
+
+impl BodySpec {
+    fn body(&self) -> Body {
+        match self {
+            BodySpec::Json(value) => Body::json(value).expect(JSON_VALUES_SERIALIZE),
+            BodySpec::JsonTemplate(template) => Body::json_template(template),
+            BodySpec::Text(text) => Body::text(text),
+            BodySpec::Form(fields) => Body::form(
+                &fields
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect::<Vec<_>>(),
+            ),
+            BodySpec::MessagePack(value) => Body::message_pack(value).expect(JSON_VALUES_SERIALIZE),
+            BodySpec::Cbor(value) => Body::cbor(value).expect(JSON_VALUES_SERIALIZE),
+            BodySpec::File {
+                content_type, data, ..
+            } => Body::bytes(content_type, data.clone()),
+        }
+    }
+}
+
+impl CheckSpec {
+    fn check(&self) -> Check {
+        match self {
+            CheckSpec::Status(status) => Check::Status(*status),
+            CheckSpec::BodyContains(text) => Check::BodyContains(text.clone()),
+            CheckSpec::JsonPointerEquals { pointer, value } => Check::JsonPointerEquals {
+                pointer: pointer.clone(),
+                value: value.clone(),
+            },
+            CheckSpec::MaxLatency(latency) => Check::MaxLatency(*latency),
+            CheckSpec::TourVisitsAllCities { cities, pointer } => {
+                visits_every_city_once(*cities, pointer)
+            }
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, ScenarioError> {
+    fs::read_to_string(path).map_err(|error| ScenarioError::Read {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    })
+}
+
+fn one() -> usize {
+    1
+}
+
+fn one_weight() -> u32 {
+    1
+}
+
+fn get() -> Method {
+    Method::GET
+}
+
+fn octet_stream() -> String {
+    String::from("application/octet-stream")
+}
+
+fn method<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Method, D::Error> {
+    let method = String::deserialize(deserializer)?;
+    Method::from_bytes(method.to_uppercase().as_bytes()).map_err(serde::de::Error::custom)
+}
+
+fn status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StatusCode, D::Error> {
+    StatusCode::from_u16(u16::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+fn milliseconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    Duration::try_from_secs_f64(f64::deserialize(deserializer)? / 1_000.0)
+        .map_err(serde::de::Error::custom)
+}
+
+fn optional_milliseconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    milliseconds(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::connection_options::HttpVersion;
+    use crate::request::interface::TimedResponse;
+    use crate::request::variables::Variables;
+
+    const YAML: &str = r#"
+host: http://localhost:8080
+virtual_users: 4
+run:
+  profile:
+    start_users: 1
+    stages:
+      - duration_secs: 10
+        target_users: 4
+      - duration_secs: 0.5
+        target_users: 0
+executor:
+  constant_arrival_rate:
+    requests_per_second: 20
+headers:
+  user-agent: loadtest
+auth:
+  bearer: secret
+think_time:
+  uniform:
+    min_ms: 500
+    max_ms: 1500
+pacing_ms: 5000
+request_mix: weighted
+seed: 7
+connection:
+  keep_alive: false
+  per_user: true
+  http_version: http1
+requests:
+  - endpoint: /alive
+    weight: 7
+    checks:
+      - status: 200
+      - max_latency_ms: 250
+  - method: post
+    endpoint: /tsp
+    timeout_ms: 2000
+    think_time:
+      constant_ms: 100
+    query:
+      generations: "1000"
+    body:
+      json:
+        distances: [[0, 1], [1, 0]]
+        n_generations: 1000
+    checks:
+      - json_pointer_equals:
+          pointer: /tour/0
+          value: 0
+thresholds:
+  - latency:
+      percentile: 95
+      max_millis: 500
+  - error_rate:
+      max: 0.01
+      endpoint: /tsp
+"#;
+
+    fn yaml() -> Scenario {
+        Scenario::parse(YAML, Format::Yaml, Path::new("")).unwrap()
+    }
+
+    #[test]
+    fn parse_yaml_scenario() {
+        let scenario = yaml();
+
+        assert_eq!(scenario.host(), "http://localhost:8080");
+        assert_eq!(scenario.virtual_users(), 4);
+        assert_eq!(
+            scenario.run_mode(),
+            RunMode::Profile(LoadProfile::new(
+                1,
+                vec![
+                    Stage::new(Duration::from_secs(10), 4),
+                    Stage::new(Duration::from_millis(500), 0)
+                ]
+            ))
+        );
+        assert_eq!(
+            scenario.executor(),
+            Executor::constant_arrival_rate(20.0).unwrap()
+        );
+        assert_eq!(
+            scenario.thresholds(),
+            &[
+                Threshold::Latency {
+                    percentile: 95.0,
+                    max_millis: 500.0,
+                    endpoint: None
+                },
+                Threshold::ErrorRate {
+                    max: 0.01,
+                    endpoint: Some(String::from("/tsp"))
+                }
+            ]
+        );
+        assert_eq!(
+            scenario.connection_options(),
+            &ConnectionOptions::default()
+                .with_keep_alive(false)
+                .with_per_user(true)
+                .with_http_version(HttpVersion::Http1)
+        );
+        assert_eq!(
+            scenario.think_time(),
+            Some(ThinkTime::Uniform {
+                min: Duration::from_millis(500),
+                max: Duration::from_millis(1_500)
+            })
+        );
+        assert_eq!(scenario.iteration_think_time(), None);
+        assert_eq!(scenario.pacing(), Some(Duration::from_secs(5)));
+        assert_eq!(scenario.request_mix(), RequestMix::Weighted);
+        assert_eq!(scenario.seed(), Some(7));
+    }
+
+    #[test]
+    fn request_definitions_from_scenario() {
+        let scenario = yaml();
+        let definitions = scenario.request_definitions();
+
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].method(), Method::GET);
+        assert_eq!(definitions[0].endpoint(), "/alive");
+        assert_eq!(
+            definitions[0]
+                .checks()
+                .iter()
+                .map(Check::to_string)
+                .collect::<Vec<_>>(),
+            vec!["status is 200", "latency <= 250ms"]
+        );
+        assert_eq!(definitions[1].method(), Method::POST);
+        assert_eq!(
+            definitions[1].query(),
+            &[(String::from("generations"), String::from("1000"))]
+        );
+        assert!(matches!(definitions[1].body(), Some(Body::Json(_))));
+        assert_eq!(definitions[0].timeout(), None);
+        assert_eq!(definitions[1].timeout(), Some(Duration::from_secs(2)));
+        assert_eq!(definitions[0].think_time(), None);
+        assert_eq!(definitions[0].weight(), 7);
+        assert_eq!(definitions[1].weight(), 1);
+        assert_eq!(
+            definitions[1].think_time(),
+            Some(ThinkTime::Constant(Duration::from_millis(100)))
+        );
+    }
+
+    #[test]
+    fn same_scenario_in_toml_and_json() {
+        let toml = r#"
+host = "http://localhost:8080"
+virtual_users = 4
+thresholds = [{ error_rate = { max = 0.01 } }]
+iteration_think_time = { exponential = { mean_ms = 2000 } }
+
+[run]
+duration_secs = 30
+
+[[requests]]
+endpoint = "/alive"
+checks = [{ status = 200 }]
+think_time = { normal = { mean_ms = 300, std_dev_ms = 50 } }
+
+[[requests]]
+method = "PUT"
+endpoint = "/users/1"
+body = { form = { name = "Steven" } }
+"#;
+        let json = r#"{
+            "host": "http://localhost:8080",
+            "virtual_users": 4,
+            "run": {"duration_secs": 30},
+            "requests": [
+                {
+                    "endpoint": "/alive",
+                    "checks": [{"status": 200}],
+                    "think_time": {"normal": {"mean_ms": 300, "std_dev_ms": 50}}
+                },
+                {"method": "PUT", "endpoint": "/users/1", "body": {"form": {"name": "Steven"}}}
+            ],
+            "thresholds": [{"error_rate": {"max": 0.01}}],
+            "iteration_think_time": {"exponential": {"mean_ms": 2000}}
+        }"#;
+
+        let from_toml = Scenario::parse(toml, Format::Toml, Path::new("")).unwrap();
+        let from_json = Scenario::parse(json, Format::Json, Path::new("")).unwrap();
+
+        assert_eq!(from_toml, from_json);
+        assert_eq!(
+            from_toml.run_mode(),
+            RunMode::Duration(Duration::from_secs(30))
+        );
+        assert_eq!(from_toml.executor(), Executor::ClosedLoop);
+        assert_eq!(from_toml.request_mix(), RequestMix::Sequential);
+        assert_eq!(from_toml.seed(), None);
+        assert_eq!(
+            from_toml.iteration_think_time(),
+            Some(ThinkTime::Exponential {
+                mean: Duration::from_secs(2)
+            })
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let scenario = yaml()
+            .with_host("https://staging.example.com")
+            .with_virtual_users(16)
+            .with_duration(Duration::from_secs(60))
+            .with_timeout(Duration::from_secs(5))
+            .with_seed(11)
+            .with_executor(Executor::ClosedLoop);
+
+        assert_eq!(scenario.host(), "https://staging.example.com");
+        assert_eq!(scenario.virtual_users(), 16);
+        assert_eq!(
+            scenario.run_mode(),
+            RunMode::Duration(Duration::from_secs(60))
+        );
+        assert_eq!(scenario.executor(), Executor::ClosedLoop);
+        assert_eq!(
+            scenario.connection_options().timeout(),
+            Some(Duration::from_secs(5))
+        );
+        assert!(!scenario.connection_options().keep_alive());
+        assert_eq!(scenario.seed(), Some(11));
+        assert_eq!(
+            scenario.with_iterations(3).run_mode(),
+            RunMode::Iterations(3)
+        );
+    }
+
+    #[test]
+    fn body_from_file() {
+        let directory = std::env::temp_dir().join("loadtest-scenario-body-from-file");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("cities.bin"), [1, 2, 3]).unwrap();
+        let path = directory.join("scenario.yaml");
+        fs::write(
+            &path,
+            r#"
+host: http://localhost
+requests:
+  - method: POST
+    endpoint: /upload
+    body:
+      file:
+        path: cities.bin
+        content_type: application/x-cities
+"#,
+        )
+        .unwrap();
+
+        let scenario = Scenario::from_path(&path).unwrap();
+
+        assert!(matches!(
+            scenario.request_definitions()[0].body(),
+            Some(Body::Bytes { content_type, data })
+                if content_type == "application/x-cities" && data.as_ref() == [1, 2, 3]
+        ));
+    }
+
+    #[test]
+    fn bundled_tsp_scenario() {
+        let scenario =
+            Scenario::from_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios/tsp.yaml"))
+                .unwrap();
+        let definitions = scenario.request_definitions();
+        let tour = serde_json::to_string(&(0..29).rev().collect::<Vec<_>>()).unwrap();
+        let response = TimedResponse::new(tour, Duration::ZERO);
+
+        assert_eq!(definitions.len(), 4);
+        assert!(definitions[3]
+            .checks()
+            .iter()
+            .all(|check| check.evaluate(&response)));
+        assert!(!definitions[2].checks()[1].evaluate(&response));
+    }
+
+    #[test]
+    fn bundled_feeder_scenario() {
+        let scenario = Scenario::from_path(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios/tsp_cities.yaml"),
+        )
+        .unwrap();
+        let mut variables = Variables::default();
+        let row = &scenario.feeders[0].rows[0];
+        for (name, value) in row {
+            variables.set(name, value.clone());
+        }
+        let definitions = scenario.request_definitions();
+        let request = definitions[0].resolve(&variables);
+
+        assert_eq!(scenario.feeders[0].rows.len(), 2);
+        assert_eq!(request.headers()[0].1, "4");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().encoded().unwrap()).unwrap();
+        assert_eq!(body["distances"][3][2], 170.0);
+    }
+
+    #[test]
+    fn feeder_from_file() {
+        let directory = std::env::temp_dir().join("loadtest-scenario-feeder-from-file");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("users.csv"), "user,password\nada,secret\n").unwrap();
+        let scenario = |feeder: &str| {
+            Scenario::parse(
+                &format!(
+                    "host: http://localhost\nfeeders:\n  - {}\nrequests:\n  - endpoint: /login",
+                    feeder
+                ),
+                Format::Yaml,
+                &directory,
+            )
+        };
+
+        let feeder =
+            &scenario("path: users.csv\n    order: unique_per_user\n    on_exhausted: stop")
+                .unwrap()
+                .feeders[0];
+        assert_eq!(feeder.order, FeedOrder::UniquePerUser);
+        assert_eq!(feeder.on_exhausted, OnExhausted::Stop);
+        assert_eq!(feeder.rows[0]["password"], "secret");
+        assert!(matches!(
+            scenario("path: missing.csv"),
+            Err(ScenarioError::Read { .. })
+        ));
+        assert!(matches!(
+            scenario("path: users.xlsx"),
+            Err(ScenarioError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_scenarios() {
+        for invalid in [
+            "host: http://localhost\nrequests: []",
+            "host: http://localhost\nrequests:\n  - endpoint: /\n    checks:\n      - status: 1000",
+            "host: http://localhost\nrequests:\n  - endpoint: /\n    method: 'not a method'",
+            "host: http://localhost\nusers: 3\nrequests:\n  - endpoint: /",
+            "host: http://localhost\nrun:\n  duration_secs: -1\nrequests:\n  - endpoint: /",
+            "host: http://localhost\nexecutor:\n  constant_arrival_rate:\n    requests_per_second: 0\nrequests:\n  - endpoint: /",
+            "host: http://localhost\nexecutor:\n  constant_arrival_rate:\n    requests_per_second: -5\nrequests:\n  - endpoint: /",
+            "host: http://localhost\nrequests:\n  - endpoint: /\n    extract:\n      id:\n        regex: '('",
+            "host: http://localhost\nrequest_mix: weighted\nrequests:\n  - endpoint: /\n    weight: 0\n  - endpoint: /alive\n    weight: 0",
+            "host: http://localhost\nrequests:\n  - endpoint: /alive\nthresholds:\n  - error_rate:\n      max: 0.1\n      endpoint: /tsp",
+            "host: http://localhost\nrequests:\n  - endpoint: /alive\nthresholds:\n  - error_rate:\n      max: 0.1\n      endpoint: POST /alive",
+        ] {
+            assert!(matches!(
+                Scenario::parse(invalid, Format::Yaml, Path::new("")),
+                Err(ScenarioError::Parse(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn chained_steps() {
+        let scenario = Scenario::parse(
+            r#"
+host: http://localhost
+requests:
+  - method: post
+    endpoint: /tsp/jobs
+    body:
+      json:
+        cities: 6
+    extract:
+      job_id:
+        json_pointer: /id
+      location:
+        header: location
+  - endpoint: /tsp/jobs/{{job_id}}
+    extract:
+      length:
+        regex: '"length":\s*([0-9.]+)'
+"#,
+            Format::Yaml,
+            Path::new(""),
+        )
+        .unwrap();
+        let definitions = scenario.request_definitions();
+
+        assert_eq!(
+            definitions[0]
+                .extractions()
+                .iter()
+                .map(|(name, extractor)| format!("{} <- {}", name, extractor))
+                .collect::<Vec<_>>(),
+            vec!["job_id <- json '/id'", "location <- header 'location'"]
+        );
+        assert_eq!(
+            definitions[1]
+                .extractions()
+                .iter()
+                .map(|(name, extractor)| format!("{} <- {}", name, extractor))
+                .collect::<Vec<_>>(),
+            vec![r#"length <- regex '"length":\s*([0-9.]+)'"#]
+        );
+    }
+
+    #[test]
+    fn missing_body_file() {
+        let error = Scenario::parse(
+            "host: http://localhost\nrequests:\n  - endpoint: /\n    body:\n      file:\n        path: missing.bin",
+            Format::Yaml,
+            Path::new("/nonexistent"),
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ScenarioError::Read { .. }));
+    }
+}
@@ -0,0 +1,237 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hdrhistogram::serialization::{Deserializer, Serializer, V2DeflateSerializer};
+use hdrhistogram::Histogram;
+use serde::de::Error;
+use std::time::Duration;
+
+const DEFAULT_HIGHEST_TRACKABLE: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_SIGNIFICANT_FIGURES: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecorderError {
+    InvalidPrecision,
+    IncompatibleRange,
+    Serialization,
+    Deserialization,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyRecorder {
+    histogram: Histogram<u64>,
+}
+
+impl LatencyRecorder {
+    pub fn new(
+        highest_trackable: Duration,
+        significant_figures: u8,
+    ) -> Result<Self, RecorderError> {
+        let highest_trackable = u64::try_from(highest_trackable.as_micros())
+            .map_err(|_| RecorderError::InvalidPrecision)?;
+        Ok(Self {
+            histogram: Histogram::new_with_bounds(1, highest_trackable.max(2), significant_figures)
+                .map_err(|_| RecorderError::InvalidPrecision)?,
+        })
+    }
+
+    /// An empty recorder with the same bounds, so the two can be merged.
+    pub fn empty_like(&self) -> Self {
+        Self {
+            histogram: Histogram::new_from(&self.histogram),
+        }
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        self.histogram
+            .saturating_record(u64::try_from(latency.as_micros()).unwrap_or(u64::MAX));
+    }
+
+    pub fn merge(&mut self, other: &LatencyRecorder) -> Result<(), RecorderError> {
+        self.histogram
+            .add(&other.histogram)
+            .map_err(|_| RecorderError::IncompatibleRange)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.histogram.is_empty()
+    }
+
+    pub fn min(&self) -> Duration {
+        Duration::from_micros(self.histogram.min())
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.histogram.max())
+    }
+
+    pub fn mean(&self) -> Duration {
+        Duration::from_secs_f64(self.histogram.mean() / 1_000_000.0)
+    }
+
+    pub fn std_dev(&self) -> Duration {
+        Duration::from_secs_f64(self.histogram.stdev() / 1_000_000.0)
+    }
+
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        Duration::from_micros(self.histogram.value_at_quantile(percentile / 100.0))
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, RecorderError> {
+        let mut serialized = vec![];
+        V2DeflateSerializer::new()
+            .serialize(&self.histogram, &mut serialized)
+            .map_err(|_| RecorderError::Serialization)?;
+        Ok(serialized)
+    }
+
+    pub fn deserialize(serialized: &[u8]) -> Result<Self, RecorderError> {
+        Ok(Self {
+            histogram: Deserializer::new()
+                .deserialize(&mut &serialized[..])
+                .map_err(|_| RecorderError::Deserialization)?,
+        })
+    }
+}
+
+impl Default for LatencyRecorder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HIGHEST_TRACKABLE, DEFAULT_SIGNIFICANT_FIGURES)
+            .expect("Default bounds are valid.")
+    }
+}
+
+impl serde::Serialize for LatencyRecorder {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let serialized = LatencyRecorder::serialize(self)
+            .map_err(|_| serde::ser::Error::custom("Could not serialize latency histogram."))?;
+        serializer.serialize_str(&STANDARD.encode(serialized))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for LatencyRecorder {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        let serialized = STANDARD.decode(encoded).map_err(D::Error::custom)?;
+        LatencyRecorder::deserialize(&serialized)
+            .map_err(|_| D::Error::custom("Could not deserialize latency histogram."))
+    }
+}
+
+/// A recorder holding `latencies`, in milliseconds.
+#[cfg(test)]
+pub(crate) fn recorder_with_millis(latencies: impl IntoIterator<Item = u64>) -> LatencyRecorder {
+    let mut recorder = LatencyRecorder::default();
+    for latency in latencies {
+        recorder.record(Duration::from_millis(latency));
+    }
+    recorder
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(actual: Duration, expected: Duration) {
+        let difference = actual.as_secs_f64() - expected.as_secs_f64();
+        assert!(
+            difference.abs() <= expected.as_secs_f64() / 100.0,
+            "{:?} is not close to {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn empty_recorder() {
+        let recorder = LatencyRecorder::default();
+
+        assert!(recorder.is_empty());
+        assert_eq!(recorder.count(), 0);
+    }
+
+    #[test]
+    fn records_statistics() {
+        let recorder = recorder_with_millis([10, 20, 30, 40]);
+
+        assert_eq!(recorder.count(), 4);
+        assert_close(recorder.min(), Duration::from_millis(10));
+        assert_close(recorder.max(), Duration::from_millis(40));
+        assert_close(recorder.mean(), Duration::from_millis(25));
+        assert_close(recorder.std_dev(), Duration::from_micros(11_180));
+    }
+
+    #[test]
+    fn percentiles() {
+        let recorder = recorder_with_millis(1..=1_000);
+
+        assert_close(recorder.percentile(50.0), Duration::from_millis(500));
+        assert_close(recorder.percentile(99.0), Duration::from_millis(990));
+        assert_close(recorder.percentile(99.9), Duration::from_millis(999));
+    }
+
+    #[test]
+    fn saturates_above_highest_trackable() {
+        let mut recorder = LatencyRecorder::new(Duration::from_secs(1), 2).unwrap();
+        recorder.record(Duration::from_secs(10));
+
+        assert_eq!(recorder.count(), 1);
+        assert_close(recorder.max(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn invalid_precision() {
+        assert_eq!(
+            LatencyRecorder::new(Duration::from_secs(1), 6),
+            Err(RecorderError::InvalidPrecision)
+        );
+    }
+
+    #[test]
+    fn merge_recorders() {
+        let mut first = recorder_with_millis([10, 20]);
+        let second = recorder_with_millis([30, 40]);
+
+        first.merge(&second).unwrap();
+
+        assert_eq!(first, recorder_with_millis([10, 20, 30, 40]));
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let recorder = recorder_with_millis([1, 5, 100]);
+
+        let serialized = recorder.serialize().unwrap();
+
+        assert_eq!(LatencyRecorder::deserialize(&serialized).unwrap(), recorder);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let recorder = recorder_with_millis([1, 5, 100]);
+
+        let json = serde_json::to_string(&recorder).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<LatencyRecorder>(&json).unwrap(),
+            recorder
+        );
+    }
+
+    #[test]
+    fn deserialize_garbage() {
+        assert_eq!(
+            LatencyRecorder::deserialize(b"not a histogram"),
+            Err(RecorderError::Deserialization)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::statistics::histogram::LatencyRecorder;
+use std::time::Duration;
+
+pub const REPORTED_PERCENTILES: [f64; 5] = [50.0, 90.0, 95.0, 99.0, 99.9];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyStatistics {
+    count: u64,
+    min: Duration,
+    max: Duration,
+    mean: Duration,
+    std_dev: Duration,
+    percentiles: Vec<(f64, Duration)>,
+}
+
+impl LatencyStatistics {
+    pub fn from_recorder(recorder: &LatencyRecorder) -> Option<Self> {
+        if recorder.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            count: recorder.count(),
+            min: recorder.min(),
+            max: recorder.max(),
+            mean: recorder.mean(),
+            std_dev: recorder.std_dev(),
+            percentiles: REPORTED_PERCENTILES
+                .iter()
+                .map(|percentile| (*percentile, recorder.percentile(*percentile)))
+                .collect(),
+        })
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> Duration {
+        self.std_dev
+    }
+
+    pub fn percentiles(&self) -> &[(f64, Duration)] {
+        &self.percentiles
+    }
+
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        self.percentiles
+            .iter()
+            .find(|(reported, _)| *reported == percentile)
+            .map(|(_, latency)| *latency)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::statistics::histogram::recorder_with_millis;
+
+    #[test]
+    fn no_latencies_no_statistics() {
+        assert_eq!(
+            LatencyStatistics::from_recorder(&LatencyRecorder::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn basic_statistics() {
+        let statistics =
+            LatencyStatistics::from_recorder(&recorder_with_millis([40, 20, 30, 10])).unwrap();
+
+        assert_eq!(statistics.count(), 4);
+        assert_eq!(statistics.min(), Duration::from_millis(10));
+        assert_eq!(statistics.max().as_millis(), 40);
+        assert_eq!(statistics.mean().as_millis(), 25);
+    }
+
+    #[test]
+    fn reports_configured_percentiles() {
+        let statistics =
+            LatencyStatistics::from_recorder(&recorder_with_millis(1..=1_000)).unwrap();
+
+        assert_eq!(
+            statistics
+                .percentiles()
+                .iter()
+                .map(|(percentile, _)| *percentile)
+                .collect::<Vec<_>>(),
+            REPORTED_PERCENTILES.to_vec()
+        );
+        assert_eq!(statistics.percentile(50.0).unwrap().as_millis(), 500);
+        assert_eq!(statistics.percentile(75.0), None);
+    }
+
+    #[test]
+    fn single_latency_is_every_percentile() {
+        let statistics = LatencyStatistics::from_recorder(&recorder_with_millis([7])).unwrap();
+
+        assert!(statistics
+            .percentiles()
+            .iter()
+            .all(|(_, latency)| latency.as_millis() == 7));
+    }
+}
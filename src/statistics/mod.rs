@@ -0,0 +1,4 @@
+pub mod histogram;
+pub mod latency;
+pub mod summary;
+pub mod threshold;
@@ -0,0 +1,795 @@
+use crate::load_test::result::UserResult;
+use crate::request::definition::RequestDefinition;
+use crate::request::interface::{to_millisecond, Phase, StatusClass};
+use crate::statistics::histogram::{LatencyRecorder, RecorderError};
+use crate::statistics::latency::{LatencyStatistics, REPORTED_PERCENTILES};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryRow {
+    name: String,
+    latencies: LatencyRecorder,
+    #[serde(default)]
+    phases: BTreeMap<Phase, LatencyRecorder>,
+    error_causes: BTreeMap<String, u64>,
+    status_classes: BTreeMap<String, u64>,
+}
+
+impl SummaryRow {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.error_causes.values().sum()
+    }
+
+    pub fn error_causes(&self) -> &BTreeMap<String, u64> {
+        &self.error_causes
+    }
+
+    pub fn status_classes(&self) -> &BTreeMap<String, u64> {
+        &self.status_classes
+    }
+
+    pub fn requests(&self) -> u64 {
+        self.latencies.count() + self.errors()
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        match self.requests() {
+            0 => 0.0,
+            requests => self.errors() as f64 / requests as f64,
+        }
+    }
+
+    pub fn latencies(&self) -> &LatencyRecorder {
+        &self.latencies
+    }
+
+    pub fn phases(&self) -> &BTreeMap<Phase, LatencyRecorder> {
+        &self.phases
+    }
+
+    pub fn statistics(&self) -> Option<LatencyStatistics> {
+        LatencyStatistics::from_recorder(&self.latencies)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckSummary {
+    definition: String,
+    check: String,
+    passes: u64,
+    fails: u64,
+}
+
+impl CheckSummary {
+    pub fn definition(&self) -> &str {
+        &self.definition
+    }
+
+    pub fn check(&self) -> &str {
+        &self.check
+    }
+
+    pub fn passes(&self) -> u64 {
+        self.passes
+    }
+
+    pub fn fails(&self) -> u64 {
+        self.fails
+    }
+
+    pub fn pass_rate(&self) -> f64 {
+        match self.passes + self.fails {
+            0 => 0.0,
+            evaluations => self.passes as f64 / evaluations as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    per_definition: Vec<SummaryRow>,
+    per_endpoint: Vec<SummaryRow>,
+    #[serde(default)]
+    per_stage: Vec<SummaryRow>,
+    checks: Vec<CheckSummary>,
+}
+
+impl Summary {
+    pub fn new(
+        request_definitions: &[RequestDefinition],
+        results: &[UserResult],
+    ) -> Result<Self, RecorderError> {
+        let mut per_definition = vec![];
+        let mut checks = vec![];
+        for (index, request_definition) in request_definitions.iter().enumerate() {
+            let name = format!(
+                "#{} {} {}",
+                index,
+                request_definition.method(),
+                request_definition.endpoint()
+            );
+            for (check_index, check) in request_definition.checks().iter().enumerate() {
+                let counts: Vec<_> = results
+                    .iter()
+                    .filter_map(|result| result.checks()[index].get(check_index))
+                    .collect();
+                checks.push(CheckSummary {
+                    definition: name.clone(),
+                    check: check.to_string(),
+                    passes: counts.iter().map(|count| count.passes()).sum(),
+                    fails: counts.iter().map(|count| count.fails()).sum(),
+                });
+            }
+
+            let mut latencies = results
+                .first()
+                .map(|result| result.latencies()[index].clone())
+                .unwrap_or_default();
+            for result in results.iter().skip(1) {
+                latencies.merge(&result.latencies()[index])?;
+            }
+            let mut phases = BTreeMap::new();
+            for result in results {
+                merge_recorders(&mut phases, &result.phases()[index])?;
+            }
+            let mut error_causes = BTreeMap::new();
+            for failure in results
+                .iter()
+                .flat_map(|result| result.failures())
+                .filter(|failure| failure.definition() == index)
+            {
+                *error_causes.entry(failure.error().to_string()).or_default() += 1;
+            }
+            let mut status_classes = BTreeMap::new();
+            for (status_class, count) in results
+                .iter()
+                .flat_map(|result| &result.status_classes()[index])
+            {
+                *status_classes.entry(status_class.to_string()).or_default() += count;
+            }
+            per_definition.push(SummaryRow {
+                name,
+                latencies,
+                phases,
+                error_causes,
+                status_classes,
+            });
+        }
+
+        let mut per_endpoint: Vec<SummaryRow> = vec![];
+        for (request_definition, definition_row) in request_definitions.iter().zip(&per_definition)
+        {
+            let name = format!(
+                "{} {}",
+                request_definition.method(),
+                request_definition.endpoint()
+            );
+            match per_endpoint.iter_mut().find(|row| row.name == name) {
+                Some(row) => {
+                    row.latencies.merge(&definition_row.latencies)?;
+                    merge_recorders(&mut row.phases, &definition_row.phases)?;
+                    merge_counts(&mut row.error_causes, &definition_row.error_causes);
+                    merge_counts(&mut row.status_classes, &definition_row.status_classes);
+                }
+                None => per_endpoint.push(SummaryRow {
+                    name,
+                    latencies: definition_row.latencies.clone(),
+                    phases: definition_row.phases.clone(),
+                    error_causes: definition_row.error_causes.clone(),
+                    status_classes: definition_row.status_classes.clone(),
+                }),
+            }
+        }
+
+        Ok(Self {
+            per_definition,
+            per_endpoint,
+            per_stage: per_stage(results)?,
+            checks,
+        })
+    }
+
+    pub fn per_definition(&self) -> &[SummaryRow] {
+        &self.per_definition
+    }
+
+    pub fn per_endpoint(&self) -> &[SummaryRow] {
+        &self.per_endpoint
+    }
+
+    /// One row per stage of a profile run, over all request definitions.
+    pub fn per_stage(&self) -> &[SummaryRow] {
+        &self.per_stage
+    }
+
+    pub fn checks(&self) -> &[CheckSummary] {
+        &self.checks
+    }
+}
+
+fn per_stage(results: &[UserResult]) -> Result<Vec<SummaryRow>, RecorderError> {
+    let mut stages = BTreeMap::new();
+    for result in results {
+        for ((stage, _), latencies) in result.stage_latencies() {
+            stage_row(&mut stages, *stage, latencies)
+                .latencies
+                .merge(latencies)?;
+        }
+        for failure in result.failures() {
+            if let Some(stage) = failure.stage() {
+                let template = &result.latencies()[failure.definition()];
+                *stage_row(&mut stages, stage, template)
+                    .error_causes
+                    .entry(failure.error().to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+    Ok(stages.into_values().collect())
+}
+
+fn stage_row<'a>(
+    stages: &'a mut BTreeMap<usize, SummaryRow>,
+    stage: usize,
+    template: &LatencyRecorder,
+) -> &'a mut SummaryRow {
+    stages.entry(stage).or_insert_with(|| SummaryRow {
+        name: format!("stage {}", stage),
+        latencies: template.empty_like(),
+        phases: BTreeMap::new(),
+        error_causes: BTreeMap::new(),
+        status_classes: BTreeMap::new(),
+    })
+}
+
+fn merge_recorders(
+    recorders: &mut BTreeMap<Phase, LatencyRecorder>,
+    other: &BTreeMap<Phase, LatencyRecorder>,
+) -> Result<(), RecorderError> {
+    for (phase, recorder) in other {
+        match recorders.get_mut(phase) {
+            Some(merged) => merged.merge(recorder)?,
+            None => {
+                recorders.insert(*phase, recorder.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn merge_counts(counts: &mut BTreeMap<String, u64>, other: &BTreeMap<String, u64>) {
+    for (key, count) in other {
+        *counts.entry(key.clone()).or_default() += count;
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Per endpoint:")?;
+        write_table(f, &self.per_endpoint)?;
+        writeln!(f)?;
+        writeln!(f, "Per request definition:")?;
+        write_table(f, &self.per_definition)?;
+        writeln!(f)?;
+        if !self.per_stage.is_empty() {
+            writeln!(f, "Per stage:")?;
+            write_table(f, &self.per_stage)?;
+            writeln!(f)?;
+        }
+        writeln!(f, "Status classes per endpoint:")?;
+        write_status_classes(f, &self.per_endpoint)?;
+        if self.per_endpoint.iter().any(|row| !row.phases.is_empty()) {
+            writeln!(f)?;
+            writeln!(f, "Timing phases per endpoint:")?;
+            write_phases(f, &self.per_endpoint)?;
+        }
+        if self.per_endpoint.iter().any(|row| row.errors() > 0) {
+            writeln!(f)?;
+            writeln!(f, "Errors per endpoint:")?;
+            write_error_causes(f, &self.per_endpoint)?;
+        }
+        if !self.checks.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Checks:")?;
+            write_checks(f, &self.checks)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_status_classes(f: &mut fmt::Formatter<'_>, rows: &[SummaryRow]) -> fmt::Result {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .chain(std::iter::once("name".len()))
+        .max()
+        .unwrap_or_default();
+    let status_classes = [
+        StatusClass::Informational,
+        StatusClass::Success,
+        StatusClass::Redirection,
+        StatusClass::ClientError,
+        StatusClass::ServerError,
+    ]
+    .map(|status_class| status_class.to_string());
+
+    write!(f, "{:<name_width$}", "name")?;
+    for status_class in &status_classes {
+        write!(f, " {:>8}", status_class)?;
+    }
+    writeln!(f)?;
+    for row in rows {
+        write!(f, "{:<name_width$}", row.name)?;
+        for status_class in &status_classes {
+            write!(
+                f,
+                " {:>8}",
+                row.status_classes
+                    .get(status_class)
+                    .copied()
+                    .unwrap_or_default()
+            )?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn write_phases(f: &mut fmt::Formatter<'_>, rows: &[SummaryRow]) -> fmt::Result {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .chain(std::iter::once("name".len()))
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        f,
+        "{:<name_width$} {:<8} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "name", "phase", "count", "mean", "p50", "p95", "p99", "max"
+    )?;
+    for row in rows {
+        for (phase, latencies) in &row.phases {
+            writeln!(
+                f,
+                "{:<name_width$} {:<8} {:>8} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+                row.name,
+                phase.to_string(),
+                latencies.count(),
+                to_millisecond(latencies.mean()),
+                to_millisecond(latencies.percentile(50.0)),
+                to_millisecond(latencies.percentile(95.0)),
+                to_millisecond(latencies.percentile(99.0)),
+                to_millisecond(latencies.max())
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_checks(f: &mut fmt::Formatter<'_>, checks: &[CheckSummary]) -> fmt::Result {
+    let definition_width = checks
+        .iter()
+        .map(|check| check.definition.len())
+        .chain(std::iter::once("name".len()))
+        .max()
+        .unwrap_or_default();
+    let check_width = checks
+        .iter()
+        .map(|check| check.check.len())
+        .chain(std::iter::once("check".len()))
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        f,
+        "{:<definition_width$} {:<check_width$} {:>8} {:>8} {:>8}",
+        "name", "check", "passes", "fails", "pass %"
+    )?;
+    for check in checks {
+        writeln!(
+            f,
+            "{:<definition_width$} {:<check_width$} {:>8} {:>8} {:>8.2}",
+            check.definition,
+            check.check,
+            check.passes,
+            check.fails,
+            check.pass_rate() * 100.0
+        )?;
+    }
+    Ok(())
+}
+
+fn write_error_causes(f: &mut fmt::Formatter<'_>, rows: &[SummaryRow]) -> fmt::Result {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .chain(std::iter::once("name".len()))
+        .max()
+        .unwrap_or_default();
+
+    writeln!(f, "{:<name_width$} {:<20} {:>8}", "name", "cause", "count")?;
+    for row in rows {
+        for (cause, count) in &row.error_causes {
+            writeln!(f, "{:<name_width$} {:<20} {:>8}", row.name, cause, count)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_table(f: &mut fmt::Formatter<'_>, rows: &[SummaryRow]) -> fmt::Result {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .chain(std::iter::once("name".len()))
+        .max()
+        .unwrap_or_default();
+
+    write!(
+        f,
+        "{:<name_width$} {:>8} {:>8} {:>8} {:>10} {:>10} {:>10}",
+        "name", "count", "errors", "err %", "min", "mean", "stddev"
+    )?;
+    for percentile in REPORTED_PERCENTILES {
+        write!(f, " {:>10}", format!("p{}", percentile))?;
+    }
+    writeln!(f, " {:>10}", "max")?;
+
+    for row in rows {
+        write!(
+            f,
+            "{:<name_width$} {:>8} {:>8} {:>8.2}",
+            row.name,
+            row.latencies.count(),
+            row.errors(),
+            row.error_rate() * 100.0
+        )?;
+        match row.statistics() {
+            Some(statistics) => {
+                write!(
+                    f,
+                    " {:>10.2} {:>10.2} {:>10.2}",
+                    to_millisecond(statistics.min()),
+                    to_millisecond(statistics.mean()),
+                    to_millisecond(statistics.std_dev())
+                )?;
+                for (_, latency) in statistics.percentiles() {
+                    write!(f, " {:>10.2}", to_millisecond(*latency))?;
+                }
+                writeln!(f, " {:>10.2}", to_millisecond(statistics.max()))?;
+            }
+            None => {
+                write!(f, " {:>10} {:>10} {:>10}", "-", "-", "-")?;
+                for _ in REPORTED_PERCENTILES {
+                    write!(f, " {:>10}", "-")?;
+                }
+                writeln!(f, " {:>10}", "-")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load_test::result::{sample_with_millis, Failure, Sample};
+    use crate::request::body::Body;
+    use crate::request::check::Check;
+    use crate::request::interface::RequestError;
+    use std::time::Duration;
+
+    fn result(user: usize, samples: Vec<Sample>) -> UserResult {
+        let mut result = UserResult::new(user, vec![LatencyRecorder::default(); 3], false);
+        for sample in samples {
+            result.record_status(sample.definition(), sample.response().status_class());
+            result.record(sample);
+        }
+        result
+    }
+
+    fn failure(definition: usize, error: RequestError) -> Failure {
+        Failure::new(
+            definition,
+            Duration::ZERO,
+            Duration::ZERO,
+            Duration::from_millis(1),
+            error,
+        )
+    }
+
+    fn definitions() -> Vec<RequestDefinition> {
+        vec![
+            RequestDefinition::get("/alive"),
+            RequestDefinition::post("/tsp", Body::json(&serde_json::json!({})).unwrap()),
+            RequestDefinition::post("/tsp", Body::json(&serde_json::json!({})).unwrap()),
+        ]
+    }
+
+    #[test]
+    fn groups_per_definition_and_endpoint() {
+        let summary = Summary::new(
+            &definitions(),
+            &[
+                result(
+                    0,
+                    vec![
+                        sample_with_millis(0, 1),
+                        sample_with_millis(1, 10),
+                        sample_with_millis(2, 30),
+                    ],
+                ),
+                result(1, vec![sample_with_millis(0, 3), sample_with_millis(1, 20)]),
+            ],
+        )
+        .unwrap();
+
+        let definition_counts: Vec<_> = summary
+            .per_definition()
+            .iter()
+            .map(|row| (row.name(), row.statistics().unwrap().count()))
+            .collect();
+        assert_eq!(
+            definition_counts,
+            vec![
+                ("#0 GET /alive", 2),
+                ("#1 POST /tsp", 2),
+                ("#2 POST /tsp", 1)
+            ]
+        );
+
+        let endpoint_means: Vec<_> = summary
+            .per_endpoint()
+            .iter()
+            .map(|row| (row.name(), row.statistics().unwrap().mean().as_millis()))
+            .collect();
+        assert_eq!(endpoint_means, vec![("GET /alive", 2), ("POST /tsp", 20)]);
+        assert_eq!(
+            summary.per_endpoint()[1]
+                .phases()
+                .iter()
+                .map(|(phase, latencies)| (*phase, latencies.count()))
+                .collect::<Vec<_>>(),
+            vec![(Phase::Headers, 3), (Phase::Body, 3)]
+        );
+    }
+
+    #[test]
+    fn separates_methods_of_an_endpoint() {
+        let summary = Summary::new(
+            &[
+                RequestDefinition::get("/tsp"),
+                RequestDefinition::post("/tsp", Body::json(&serde_json::json!({})).unwrap()),
+                RequestDefinition::get("/tsp"),
+            ],
+            &[result(
+                0,
+                vec![
+                    sample_with_millis(0, 1),
+                    sample_with_millis(1, 10),
+                    sample_with_millis(2, 3),
+                ],
+            )],
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary
+                .per_endpoint()
+                .iter()
+                .map(|row| (row.name(), row.latencies().count()))
+                .collect::<Vec<_>>(),
+            vec![("GET /tsp", 2), ("POST /tsp", 1)]
+        );
+    }
+
+    #[test]
+    fn definitions_without_samples_have_no_statistics() {
+        let summary = Summary::new(&definitions(), &[]).unwrap();
+
+        assert!(summary
+            .per_definition()
+            .iter()
+            .all(|row| row.statistics().is_none()));
+        assert_eq!(summary.per_endpoint().len(), 2);
+    }
+
+    #[test]
+    fn display_table() {
+        let summary = Summary::new(
+            &[RequestDefinition::get("/alive")],
+            &[result(0, vec![sample_with_millis(0, 2)])],
+        )
+        .unwrap();
+
+        assert_eq!(
+            format!("{}", summary),
+            [
+                "Per endpoint:",
+                "name          count   errors    err %        min       mean     stddev        p50        p90        p95        p99      p99.9        max",
+                "GET /alive        1        0     0.00       2.00       2.00       0.00       2.00       2.00       2.00       2.00       2.00       2.00",
+                "",
+                "Per request definition:",
+                "name             count   errors    err %        min       mean     stddev        p50        p90        p95        p99      p99.9        max",
+                "#0 GET /alive        1        0     0.00       2.00       2.00       0.00       2.00       2.00       2.00       2.00       2.00       2.00",
+                "",
+                "Status classes per endpoint:",
+                "name            1xx      2xx      3xx      4xx      5xx",
+                "GET /alive        0        1        0        0        0",
+                "",
+                "Timing phases per endpoint:",
+                "name       phase       count       mean        p50        p95        p99        max",
+                "GET /alive headers         1       2.00       2.00       2.00       2.00       2.00",
+                "GET /alive body            1       0.00       0.00       0.00       0.00       0.00",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn counts_errors_per_definition_and_endpoint() {
+        let mut failing_user = result(0, vec![sample_with_millis(0, 1), sample_with_millis(1, 10)]);
+        failing_user.record_failure(failure(1, RequestError::Timeout));
+        failing_user.record_failure(failure(2, RequestError::Status(500)));
+        failing_user.record_failure(failure(2, RequestError::Timeout));
+
+        let summary = Summary::new(&definitions(), &[failing_user]).unwrap();
+
+        let definition_errors: Vec<_> = summary
+            .per_definition()
+            .iter()
+            .map(|row| (row.errors(), row.requests(), row.error_rate()))
+            .collect();
+        assert_eq!(
+            definition_errors,
+            vec![(0, 1, 0.0), (1, 2, 0.5), (2, 2, 1.0)]
+        );
+
+        let endpoint_errors: Vec<_> = summary
+            .per_endpoint()
+            .iter()
+            .map(|row| (row.name(), row.errors(), row.error_rate()))
+            .collect();
+        assert_eq!(
+            endpoint_errors,
+            vec![("GET /alive", 0, 0.0), ("POST /tsp", 3, 0.75)]
+        );
+
+        assert_eq!(
+            summary.per_endpoint()[1].status_classes(),
+            &BTreeMap::from([(String::from("2xx"), 1)])
+        );
+        assert_eq!(
+            summary.per_endpoint()[1].error_causes(),
+            &BTreeMap::from([
+                (String::from("status 500"), 1),
+                (String::from("timeout"), 2)
+            ])
+        );
+    }
+
+    #[test]
+    fn groups_per_stage() {
+        let mut first_user = result(
+            0,
+            vec![
+                sample_with_millis(0, 1).with_stage(Some(0)),
+                sample_with_millis(1, 2).with_stage(Some(1)),
+            ],
+        );
+        first_user.record_failure(failure(1, RequestError::Timeout).with_stage(Some(1)));
+        let second_user = result(1, vec![sample_with_millis(0, 3).with_stage(Some(1))]);
+
+        let summary = Summary::new(&definitions(), &[first_user, second_user]).unwrap();
+
+        assert_eq!(
+            summary
+                .per_stage()
+                .iter()
+                .map(|row| (row.name(), row.latencies().count(), row.errors()))
+                .collect::<Vec<_>>(),
+            vec![("stage 0", 1, 0), ("stage 1", 2, 1)]
+        );
+        assert!(format!("{}", summary).contains("Per stage:\nname "));
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(serde_json::from_str::<Summary>(&json).unwrap(), summary);
+    }
+
+    #[test]
+    fn no_stages_without_profile() {
+        let summary = Summary::new(
+            &[RequestDefinition::get("/alive")],
+            &[result(0, vec![sample_with_millis(0, 1)])],
+        )
+        .unwrap();
+
+        assert!(summary.per_stage().is_empty());
+        assert!(!format!("{}", summary).contains("Per stage:"));
+    }
+
+    #[test]
+    fn display_error_causes() {
+        let mut failing_user = result(0, vec![]);
+        failing_user.record_failure(failure(0, RequestError::Dns));
+
+        let summary = Summary::new(&[RequestDefinition::get("/alive")], &[failing_user]).unwrap();
+
+        assert!(format!("{}", summary).ends_with(
+            &[
+                "Errors per endpoint:",
+                "name       cause                   count",
+                "GET /alive dns                         1",
+                "",
+            ]
+            .join("\n")
+        ));
+    }
+
+    #[test]
+    fn no_requests_no_error_rate() {
+        let summary = Summary::new(&[RequestDefinition::get("/alive")], &[]).unwrap();
+
+        assert_eq!(summary.per_endpoint()[0].error_rate(), 0.0);
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let summary = Summary::new(
+            &[RequestDefinition::get("/alive")],
+            &[result(
+                0,
+                vec![sample_with_millis(0, 10), sample_with_millis(0, 12)],
+            )],
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&summary).unwrap();
+
+        assert_eq!(serde_json::from_str::<Summary>(&json).unwrap(), summary);
+    }
+
+    #[test]
+    fn counts_checks_per_definition() {
+        let mut user = result(0, vec![sample_with_millis(0, 1)]);
+        user.record_check(0, 0, true);
+        user.record_check(0, 1, false);
+        let mut other_user = result(1, vec![sample_with_millis(0, 1)]);
+        other_user.record_check(0, 0, true);
+        other_user.record_check(0, 1, true);
+
+        let summary = Summary::new(
+            &[RequestDefinition::get("/alive").with_checks(vec![
+                Check::Status(http::StatusCode::OK),
+                Check::BodyContains(String::from("alive")),
+            ])],
+            &[user, other_user],
+        )
+        .unwrap();
+
+        assert_eq!(
+            summary
+                .checks()
+                .iter()
+                .map(|check| (check.check(), check.passes(), check.fails()))
+                .collect::<Vec<_>>(),
+            vec![("status is 200", 2, 0), ("body contains 'alive'", 1, 1)]
+        );
+        assert!(format!("{}", summary).ends_with(
+            &[
+                "Checks:",
+                "name          check                   passes    fails   pass %",
+                "#0 GET /alive status is 200                2        0   100.00",
+                "#0 GET /alive body contains 'alive'        1        1    50.00",
+                "",
+            ]
+            .join("\n")
+        ));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::statistics::summary::{Summary, SummaryRow};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Pass/fail criteria for a finished load test. Latency and error rate thresholds
+/// without an endpoint apply to every endpoint of the summary. An endpoint is either
+/// `METHOD path` or a path alone, which matches it with every method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    Latency {
+        percentile: f64,
+        max_millis: f64,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    ErrorRate {
+        max: f64,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    CheckPassRate {
+        min: f64,
+    },
+}
+
+impl Threshold {
+    pub fn evaluate(&self, summary: &Summary) -> Vec<Violation> {
+        match self {
+            Threshold::Latency {
+                percentile,
+                max_millis,
+                endpoint,
+            } => rows(summary, endpoint)
+                .filter(|row| !row.latencies().is_empty())
+                .map(|row| {
+                    let actual = row.latencies().percentile(*percentile).as_secs_f64() * 1_000.0;
+                    (row.name(), actual)
+                })
+                .filter(|(_, actual)| actual > max_millis)
+                .map(|(name, actual)| self.violation(name, actual))
+                .collect(),
+            Threshold::ErrorRate { max, endpoint } => rows(summary, endpoint)
+                .map(|row| (row.name(), row.error_rate()))
+                .filter(|(_, actual)| actual > max)
+                .map(|(name, actual)| self.violation(name, actual))
+                .collect(),
+            Threshold::CheckPassRate { min } => summary
+                .checks()
+                .iter()
+                .map(|check| {
+                    (
+                        format!("{} {}", check.definition(), check.check()),
+                        check.pass_rate(),
+                    )
+                })
+                .filter(|(_, actual)| actual < min)
+                .map(|(name, actual)| self.violation(&name, actual))
+                .collect(),
+        }
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            Threshold::Latency { endpoint, .. } | Threshold::ErrorRate { endpoint, .. } => {
+                endpoint.as_deref()
+            }
+            Threshold::CheckPassRate { .. } => None,
+        }
+    }
+
+    fn violation(&self, subject: &str, actual: f64) -> Violation {
+        Violation {
+            threshold: self.clone(),
+            subject: subject.to_string(),
+            actual,
+        }
+    }
+}
+
+fn rows<'a>(
+    summary: &'a Summary,
+    endpoint: &'a Option<String>,
+) -> impl Iterator<Item = &'a SummaryRow> {
+    summary.per_endpoint().iter().filter(move |row| {
+        endpoint
+            .as_ref()
+            .is_none_or(|endpoint| matches_endpoint(endpoint, row.name()))
+    })
+}
+
+/// Whether a threshold `endpoint` selects the summary row named `METHOD path`.
+pub(crate) fn matches_endpoint(endpoint: &str, row_name: &str) -> bool {
+    row_name == endpoint
+        || row_name
+            .split_once(' ')
+            .is_some_and(|(_, path)| path == endpoint)
+}
+
+pub fn evaluate_all(thresholds: &[Threshold], summary: &Summary) -> Vec<Violation> {
+    thresholds
+        .iter()
+        .flat_map(|threshold| threshold.evaluate(summary))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    threshold: Threshold,
+    subject: String,
+    actual: f64,
+}
+
+impl Violation {
+    pub fn threshold(&self) -> &Threshold {
+        &self.threshold
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn actual(&self) -> f64 {
+        self.actual
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.threshold {
+            Threshold::Latency {
+                percentile,
+                max_millis,
+                ..
+            } => write!(
+                f,
+                "{}: p{} latency {:.3}ms exceeds {}ms",
+                self.subject, percentile, self.actual, max_millis
+            ),
+            Threshold::ErrorRate { max, .. } => write!(
+                f,
+                "{}: error rate {:.2}% exceeds {:.2}%",
+                self.subject,
+                self.actual * 100.0,
+                max * 100.0
+            ),
+            Threshold::CheckPassRate { min } => write!(
+                f,
+                "{}: pass rate {:.2}% below {:.2}%",
+                self.subject,
+                self.actual * 100.0,
+                min * 100.0
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load_test::result::{Failure, Sample, UserResult};
+    use crate::request::check::Check;
+    use crate::request::definition::RequestDefinition;
+    use crate::request::interface::{RequestError, TimedResponse};
+    use crate::statistics::histogram::LatencyRecorder;
+    use http::StatusCode;
+    use std::time::Duration;
+
+    fn summary() -> Summary {
+        let definitions = [
+            RequestDefinition::get("/alive").with_checks(vec![Check::Status(StatusCode::OK)]),
+            RequestDefinition::get("/tsp").with_checks(vec![Check::Status(StatusCode::OK)]),
+        ];
+        let mut result = UserResult::new(0, vec![LatencyRecorder::default(); 2], false);
+        for (definition, millis) in [(0, 2), (0, 2), (1, 100), (1, 300)] {
+            result.record(Sample::new(
+                definition,
+                Duration::ZERO,
+                Duration::ZERO,
+                TimedResponse::new(String::new(), Duration::from_millis(millis)),
+            ));
+        }
+        result.record_failure(Failure::new(
+            1,
+            Duration::ZERO,
+            Duration::ZERO,
+            Duration::from_millis(5),
+            RequestError::Timeout,
+        ));
+        result.record_check(0, 0, true);
+        result.record_check(1, 0, false);
+        Summary::new(&definitions, &[result]).unwrap()
+    }
+
+    #[test]
+    fn latency_threshold_per_endpoint() {
+        let threshold = Threshold::Latency {
+            percentile: 99.0,
+            max_millis: 50.0,
+            endpoint: None,
+        };
+
+        let violations = threshold.evaluate(&summary());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].subject(), "GET /tsp");
+        assert!((violations[0].actual() - 300.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn threshold_for_single_endpoint() {
+        let threshold = Threshold::Latency {
+            percentile: 50.0,
+            max_millis: 1.0,
+            endpoint: Some(String::from("/alive")),
+        };
+
+        let violations = threshold.evaluate(&summary());
+
+        assert_eq!(
+            violations
+                .iter()
+                .map(Violation::subject)
+                .collect::<Vec<_>>(),
+            vec!["GET /alive"]
+        );
+        let by_method = Threshold::Latency {
+            percentile: 50.0,
+            max_millis: 1.0,
+            endpoint: Some(String::from("POST /alive")),
+        };
+        assert!(by_method.evaluate(&summary()).is_empty());
+    }
+
+    #[test]
+    fn error_rate_threshold() {
+        let violations = evaluate_all(
+            &[
+                Threshold::ErrorRate {
+                    max: 0.5,
+                    endpoint: None,
+                },
+                Threshold::ErrorRate {
+                    max: 0.1,
+                    endpoint: None,
+                },
+            ],
+            &summary(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "GET /tsp: error rate 33.33% exceeds 10.00%"
+        );
+    }
+
+    #[test]
+    fn check_pass_rate_threshold() {
+        let violations = Threshold::CheckPassRate { min: 0.99 }.evaluate(&summary());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].subject(), "#1 GET /tsp status is 200");
+        assert_eq!(violations[0].actual(), 0.0);
+    }
+}
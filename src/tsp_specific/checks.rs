@@ -0,0 +1,75 @@
+use crate::request::check::Check;
+
+pub fn visits_every_city_once(cities: usize, tour_pointer: &str) -> Check {
+    let tour_pointer = tour_pointer.to_string();
+    Check::custom(
+        &format!("tour visits all {} cities once", cities),
+        move |response| {
+            let Ok(body) = serde_json::from_str::<serde_json::Value>(response.text()) else {
+                return false;
+            };
+            let Some(tour) = body.pointer(&tour_pointer).and_then(|tour| tour.as_array()) else {
+                return false;
+            };
+
+            let mut visited = vec![false; cities];
+            tour.len() == cities
+                && tour.iter().all(|city| match city.as_u64() {
+                    Some(city) if (city as usize) < cities && !visited[city as usize] => {
+                        visited[city as usize] = true;
+                        true
+                    }
+                    _ => false,
+                })
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::interface::text_response;
+
+    #[test]
+    fn valid_tour() {
+        let check = visits_every_city_once(4, "/tour");
+
+        assert!(check.evaluate(&text_response(r#"{"tour": [2, 0, 3, 1]}"#)));
+    }
+
+    #[test]
+    fn tour_at_top_level() {
+        assert!(visits_every_city_once(3, "").evaluate(&text_response("[1, 2, 0]")));
+    }
+
+    #[test]
+    fn repeated_city() {
+        assert!(!visits_every_city_once(3, "").evaluate(&text_response("[1, 1, 0]")));
+    }
+
+    #[test]
+    fn missing_city() {
+        assert!(!visits_every_city_once(3, "").evaluate(&text_response("[1, 0]")));
+    }
+
+    #[test]
+    fn unknown_city() {
+        assert!(!visits_every_city_once(3, "").evaluate(&text_response("[1, 0, 3]")));
+    }
+
+    #[test]
+    fn no_tour() {
+        let check = visits_every_city_once(3, "/tour");
+
+        assert!(!check.evaluate(&text_response(r#"{"length": 3}"#)));
+        assert!(!check.evaluate(&text_response("no json")));
+    }
+
+    #[test]
+    fn name() {
+        assert_eq!(
+            format!("{}", visits_every_city_once(6, "")),
+            "tour visits all 6 cities once"
+        );
+    }
+}
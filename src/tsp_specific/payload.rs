@@ -13,4 +13,8 @@ impl SolveTspData {
             n_generations,
         }
     }
+
+    pub fn cities(&self) -> usize {
+        self.distances.len()
+    }
 }